@@ -0,0 +1,105 @@
+//! Page counts of PDFs already opened this session
+//!
+//! Entries are keyed by path, modified time and size, so a file that is
+//! replaced or edited on disk is parsed again rather than served stale.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::pdf::{load_document, PdfError};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct FileKey {
+    path: PathBuf,
+    modified: SystemTime,
+    size: u64,
+}
+
+impl FileKey {
+    fn of(path: &str) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(FileKey {
+            path: PathBuf::from(path),
+            modified: metadata.modified().ok()?,
+            size: metadata.len(),
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct PdfCache {
+    page_counts: std::sync::Mutex<HashMap<FileKey, usize>>,
+}
+
+impl PdfCache {
+    /// The cached page count, if the file is unchanged since it was counted
+    pub fn cached_page_count(&self, path: &str) -> Option<usize> {
+        let key = FileKey::of(path)?;
+        self.page_counts.lock().unwrap().get(&key).copied()
+    }
+
+    /// Remember a page count found by another operation, such as metadata extraction
+    pub fn record_page_count(&self, path: &str, page_count: usize) {
+        if let Some(key) = FileKey::of(path) {
+            self.page_counts.lock().unwrap().insert(key, page_count);
+        }
+    }
+
+    /// Page count of a PDF, parsing it only on a cache miss
+    pub fn page_count(&self, path: &str, password: Option<&str>) -> Result<usize, PdfError> {
+        self.page_count_with(path, |path| {
+            load_document(path, password).map(|doc| doc.get_pages().len())
+        })
+    }
+
+    fn page_count_with(
+        &self,
+        path: &str,
+        count: impl FnOnce(&str) -> Result<usize, PdfError>,
+    ) -> Result<usize, PdfError> {
+        let key = FileKey::of(path).ok_or_else(|| PdfError::NotFound(path.to_string()))?;
+        if let Some(&cached) = self.page_counts.lock().unwrap().get(&key) {
+            return Ok(cached);
+        }
+        let page_count = count(path)?;
+        self.page_counts.lock().unwrap().insert(key, page_count);
+        Ok(page_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::fixtures::{temp_pdf_path, write_text_pdf};
+    use std::cell::Cell;
+
+    #[test]
+    fn test_unchanged_file_is_parsed_once() {
+        let path = temp_pdf_path("cache");
+        write_text_pdf(&path, &["One", "Two"]);
+        let path_str = path.to_str().unwrap();
+
+        let cache = PdfCache::default();
+        let parses = Cell::new(0);
+        let counting = |path: &str| {
+            parses.set(parses.get() + 1);
+            Ok(lopdf::Document::load(path).unwrap().get_pages().len())
+        };
+
+        assert_eq!(cache.page_count_with(path_str, counting).unwrap(), 2);
+        assert_eq!(cache.page_count_with(path_str, counting).unwrap(), 2);
+        assert_eq!(parses.get(), 1);
+
+        // A different size means a different file
+        write_text_pdf(&path, &["One", "Two", "Three"]);
+        assert_eq!(cache.page_count_with(path_str, counting).unwrap(), 3);
+        assert_eq!(parses.get(), 2);
+        std::fs::remove_file(&path).ok();
+
+        assert!(matches!(
+            cache.page_count(path_str, None),
+            Err(PdfError::NotFound(_))
+        ));
+    }
+}
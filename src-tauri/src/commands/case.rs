@@ -1,22 +1,36 @@
 //! Case commands - CRUD operations for cases
 
-use crate::db;
-use crate::{AppState, Case, CreateCaseRequest};
+use tauri::Manager;
+
+use crate::db::{self, DbError};
+use crate::pdf::{self, NameIndexEntry};
+use crate::{
+    AppState, Case, CaseExportResult, CasePage, CaseSection, CaseStats, ContentValidationResult,
+    CreateCaseRequest,
+};
+
+/// Resolves once the database is ready, for the frontend to gate its first queries
+#[tauri::command]
+pub async fn wait_for_db(state: tauri::State<'_, AppState>) -> Result<(), DbError> {
+    state.pool().await.map(|_| ())
+}
 
 #[tauri::command]
-pub async fn list_cases(state: tauri::State<'_, AppState>) -> Result<Vec<Case>, String> {
-    let db_guard = state.db.lock().await;
-    let pool = db_guard.as_ref().ok_or("Database not initialized")?;
-    db::list_cases(pool).await
+pub async fn list_cases(
+    limit: Option<i64>,
+    offset: Option<i64>,
+    state: tauri::State<'_, AppState>,
+) -> Result<CasePage, DbError> {
+    let pool = &state.pool().await?;
+    db::list_cases(pool, limit, offset).await
 }
 
 #[tauri::command]
 pub async fn create_case(
     request: CreateCaseRequest,
     state: tauri::State<'_, AppState>,
-) -> Result<Case, String> {
-    let db_guard = state.db.lock().await;
-    let pool = db_guard.as_ref().ok_or("Database not initialized")?;
+) -> Result<Case, DbError> {
+    let pool = &state.pool().await?;
     db::create_case(
         pool,
         &request.name,
@@ -27,8 +41,245 @@ pub async fn create_case(
 }
 
 #[tauri::command]
-pub async fn delete_case(id: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
-    let db_guard = state.db.lock().await;
-    let pool = db_guard.as_ref().ok_or("Database not initialized")?;
+pub async fn rename_case(
+    id: String,
+    new_name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Case, DbError> {
+    let pool = &state.pool().await?;
+    db::rename_case(pool, &id, &new_name).await
+}
+
+#[tauri::command]
+pub async fn set_last_output_dir(
+    case_id: String,
+    dir: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), DbError> {
+    let pool = &state.pool().await?;
+    db::set_last_output_dir(pool, &case_id, &dir).await
+}
+
+#[tauri::command]
+pub async fn get_last_output_dir(
+    case_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<String>, DbError> {
+    let pool = &state.pool().await?;
+    db::get_last_output_dir(pool, &case_id).await
+}
+
+#[tauri::command]
+pub async fn record_compile(
+    case_id: String,
+    page_count: u32,
+    duration_ms: u64,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), DbError> {
+    let pool = &state.pool().await?;
+    db::record_compile(pool, &case_id, page_count, duration_ms).await
+}
+
+/// Estimated compile time in milliseconds
+#[tauri::command]
+pub async fn estimate_compile_time(
+    total_pages: u32,
+    state: tauri::State<'_, AppState>,
+) -> Result<u64, DbError> {
+    let pool = &state.pool().await?;
+    let estimate = db::estimate_compile_time(pool, total_pages).await?;
+    Ok(estimate.as_millis() as u64)
+}
+
+#[tauri::command]
+pub async fn build_name_index(
+    case_id: String,
+    exclude: Vec<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<NameIndexEntry>, DbError> {
+    let pool = &state.pool().await?;
+    let sources = db::name_index_sources(pool, &case_id).await?;
+    Ok(state
+        .pdf_limiter
+        .run(move || pdf::build_name_index(&sources, &exclude))
+        .await)
+}
+
+#[tauri::command]
+pub async fn delete_case(id: String, state: tauri::State<'_, AppState>) -> Result<(), DbError> {
+    let pool = &state.pool().await?;
     db::delete_case(pool, &id).await
 }
+
+/// Copy a case with its files, entries and content under a new name
+#[tauri::command]
+pub async fn duplicate_case(
+    id: String,
+    new_name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Case, DbError> {
+    let pool = &state.pool().await?;
+    db::duplicate_case(pool, &id, &new_name).await
+}
+
+/// Write a case and its PDFs to a ZIP archive
+#[tauri::command]
+pub async fn export_case(
+    case_id: String,
+    output_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<CaseExportResult, DbError> {
+    let pool = &state.pool().await?;
+    let archive = db::load_case_archive(pool, &case_id).await?;
+    // Reads and deflates every PDF in the case
+    state
+        .pdf_limiter
+        .run(move || db::write_case_zip(archive, &output_path))
+        .await
+}
+
+/// Create a new case from an exported archive, copying its PDFs into app data
+#[tauri::command]
+pub async fn import_case(
+    zip_path: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<Case, DbError> {
+    let files_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| DbError::Invalid(format!("Failed to find app data directory: {}", e)))?
+        .join("imports");
+    let pool = &state.pool().await?;
+    let unpacked = state
+        .pdf_limiter
+        .run(move || db::unpack_case_zip(&zip_path, &files_dir))
+        .await?;
+    match db::insert_case_archive(pool, &unpacked).await {
+        Ok(case) => Ok(case),
+        Err(e) => {
+            state.pdf_limiter.run(move || unpacked.discard()).await;
+            Err(e)
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn list_trashed_cases(state: tauri::State<'_, AppState>) -> Result<Vec<Case>, DbError> {
+    let pool = &state.pool().await?;
+    db::list_trashed_cases(pool).await
+}
+
+#[tauri::command]
+pub async fn restore_case(id: String, state: tauri::State<'_, AppState>) -> Result<(), DbError> {
+    let pool = &state.pool().await?;
+    db::restore_case(pool, &id).await
+}
+
+#[tauri::command]
+pub async fn purge_case(id: String, state: tauri::State<'_, AppState>) -> Result<(), DbError> {
+    let pool = &state.pool().await?;
+    db::purge_case(pool, &id).await
+}
+
+/// Document, file and page totals for the case dashboard
+#[tauri::command]
+pub async fn case_stats(
+    case_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<CaseStats, DbError> {
+    let pool = &state.pool().await?;
+    db::case_stats(pool, &case_id).await
+}
+
+#[tauri::command]
+pub async fn validate_content_json(
+    case_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ContentValidationResult, DbError> {
+    let pool = &state.pool().await?;
+    db::validate_content_json(pool, &case_id).await
+}
+
+#[tauri::command]
+pub async fn normalize_case_content(
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<bool, DbError> {
+    let pool = &state.pool().await?;
+    db::normalize_case_content(pool, &id).await
+}
+
+#[tauri::command]
+pub async fn save_bundle_draft(
+    case_id: String,
+    draft_json: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), DbError> {
+    let pool = &state.pool().await?;
+    db::save_bundle_draft(pool, &case_id, &draft_json).await
+}
+
+#[tauri::command]
+pub async fn load_bundle_draft(
+    case_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<String>, DbError> {
+    let pool = &state.pool().await?;
+    db::load_bundle_draft(pool, &case_id).await
+}
+
+#[tauri::command]
+pub async fn migrate_case_content(
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<bool, DbError> {
+    let pool = &state.pool().await?;
+    db::migrate_case_content(pool, &id).await
+}
+
+#[tauri::command]
+pub async fn migrate_all_content(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, DbError> {
+    let pool = &state.pool().await?;
+    db::migrate_all_content(pool).await
+}
+
+#[tauri::command]
+pub async fn split_case_content(
+    case_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<CaseSection>, DbError> {
+    let pool = &state.pool().await?;
+    db::split_case_content(pool, &case_id).await
+}
+
+#[tauri::command]
+pub async fn list_case_sections(
+    case_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<CaseSection>, DbError> {
+    let pool = &state.pool().await?;
+    db::list_case_sections(pool, &case_id).await
+}
+
+#[tauri::command]
+pub async fn update_case_section(
+    id: String,
+    heading: Option<String>,
+    content: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<CaseSection, DbError> {
+    let pool = &state.pool().await?;
+    db::update_case_section(pool, &id, heading.as_deref(), &content).await
+}
+
+#[tauri::command]
+pub async fn assemble_case_content(
+    case_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, DbError> {
+    let pool = &state.pool().await?;
+    db::assemble_case_content(pool, &case_id).await
+}
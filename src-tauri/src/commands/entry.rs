@@ -1,6 +1,6 @@
 //! Entry commands - Artifact entry operations (linking files/components to cases)
 
-use crate::db;
+use crate::db::{self, DbError};
 use crate::{
     AppState, ArtifactEntry, CreateEntryRequest, ReorderEntriesRequest, UpdateEntryRequest,
 };
@@ -9,9 +9,8 @@ use crate::{
 pub async fn list_entries(
     case_id: String,
     state: tauri::State<'_, AppState>,
-) -> Result<Vec<ArtifactEntry>, String> {
-    let db_guard = state.db.lock().await;
-    let pool = db_guard.as_ref().ok_or("Database not initialized")?;
+) -> Result<Vec<ArtifactEntry>, DbError> {
+    let pool = &state.pool().await?;
     db::list_entries(pool, &case_id).await
 }
 
@@ -19,9 +18,8 @@ pub async fn list_entries(
 pub async fn create_entry(
     request: CreateEntryRequest,
     state: tauri::State<'_, AppState>,
-) -> Result<ArtifactEntry, String> {
-    let db_guard = state.db.lock().await;
-    let pool = db_guard.as_ref().ok_or("Database not initialized")?;
+) -> Result<ArtifactEntry, DbError> {
+    let pool = &state.pool().await?;
     db::create_entry(
         pool,
         &request.case_id,
@@ -38,9 +36,8 @@ pub async fn create_entry(
 pub async fn update_entry(
     request: UpdateEntryRequest,
     state: tauri::State<'_, AppState>,
-) -> Result<ArtifactEntry, String> {
-    let db_guard = state.db.lock().await;
-    let pool = db_guard.as_ref().ok_or("Database not initialized")?;
+) -> Result<ArtifactEntry, DbError> {
+    let pool = &state.pool().await?;
     db::update_entry(
         pool,
         &request.id,
@@ -52,9 +49,8 @@ pub async fn update_entry(
 }
 
 #[tauri::command]
-pub async fn delete_entry(id: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
-    let db_guard = state.db.lock().await;
-    let pool = db_guard.as_ref().ok_or("Database not initialized")?;
+pub async fn delete_entry(id: String, state: tauri::State<'_, AppState>) -> Result<(), DbError> {
+    let pool = &state.pool().await?;
     db::delete_entry(pool, &id).await
 }
 
@@ -62,8 +58,7 @@ pub async fn delete_entry(id: String, state: tauri::State<'_, AppState>) -> Resu
 pub async fn reorder_entries(
     request: ReorderEntriesRequest,
     state: tauri::State<'_, AppState>,
-) -> Result<Vec<ArtifactEntry>, String> {
-    let db_guard = state.db.lock().await;
-    let pool = db_guard.as_ref().ok_or("Database not initialized")?;
+) -> Result<Vec<ArtifactEntry>, DbError> {
+    let pool = &state.pool().await?;
     db::reorder_entries(pool, &request.case_id, request.entry_ids).await
 }
@@ -1,25 +1,76 @@
 //! File commands - Repository file operations
 
-use crate::db;
-use crate::{AppState, CreateFileRequest, File, UpdateFileRequest};
+use crate::db::{self, DbError};
+use crate::pdf;
+use crate::{
+    AppState, CreateFileRequest, DocumentTypeCount, File, FileFilter, MetadataFieldValue,
+    RelinkResult, UpdateFileRequest,
+};
 
 #[tauri::command]
 pub async fn list_files(
     case_id: String,
     state: tauri::State<'_, AppState>,
-) -> Result<Vec<File>, String> {
-    let db_guard = state.db.lock().await;
-    let pool = db_guard.as_ref().ok_or("Database not initialized")?;
+) -> Result<Vec<File>, DbError> {
+    let pool = &state.pool().await?;
     db::list_files(pool, &case_id).await
 }
 
+#[tauri::command]
+pub async fn list_files_in_bundle_order(
+    case_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<File>, DbError> {
+    let pool = &state.pool().await?;
+    db::list_files_in_bundle_order(pool, &case_id).await
+}
+
+#[tauri::command]
+pub async fn collect_metadata_field(
+    case_id: String,
+    field: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<MetadataFieldValue>, DbError> {
+    let pool = &state.pool().await?;
+    db::collect_metadata_field(pool, &case_id, &field).await
+}
+
+#[tauri::command]
+pub async fn list_files_filtered(
+    case_id: String,
+    filter: FileFilter,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<File>, DbError> {
+    let pool = &state.pool().await?;
+    db::list_files_filtered(pool, &case_id, &filter).await
+}
+
+#[tauri::command]
+pub async fn list_document_types(
+    case_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<DocumentTypeCount>, DbError> {
+    let pool = &state.pool().await?;
+    db::list_document_types(pool, &case_id).await
+}
+
+#[tauri::command]
+pub async fn ensure_page_counts(
+    case_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, DbError> {
+    let pool = &state.pool().await?;
+    db::ensure_page_counts(pool, &case_id).await
+}
+
 #[tauri::command]
 pub async fn create_file(
     request: CreateFileRequest,
     state: tauri::State<'_, AppState>,
-) -> Result<File, String> {
-    let db_guard = state.db.lock().await;
-    let pool = db_guard.as_ref().ok_or("Database not initialized")?;
+) -> Result<File, DbError> {
+    let path = request.path.clone();
+    let hashes = state.pdf_limiter.run(move || pdf::file_hashes(&path)).await;
+    let pool = &state.pool().await?;
     db::create_file(
         pool,
         &request.case_id,
@@ -27,14 +78,34 @@ pub async fn create_file(
         &request.original_name,
         request.page_count,
         request.metadata_json.as_deref(),
+        &hashes,
     )
     .await
 }
 
 #[tauri::command]
-pub async fn get_file(id: String, state: tauri::State<'_, AppState>) -> Result<File, String> {
-    let db_guard = state.db.lock().await;
-    let pool = db_guard.as_ref().ok_or("Database not initialized")?;
+pub async fn find_file_by_hash(
+    case_id: String,
+    hash: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<File>, DbError> {
+    let pool = &state.pool().await?;
+    db::find_file_by_hash(pool, &case_id, &hash).await
+}
+
+#[tauri::command]
+pub async fn find_visual_duplicates(
+    case_id: String,
+    max_distance: u32,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<Vec<File>>, DbError> {
+    let pool = &state.pool().await?;
+    db::find_visual_duplicates(pool, &case_id, max_distance).await
+}
+
+#[tauri::command]
+pub async fn get_file(id: String, state: tauri::State<'_, AppState>) -> Result<File, DbError> {
+    let pool = &state.pool().await?;
     db::get_file(pool, &id).await
 }
 
@@ -42,9 +113,8 @@ pub async fn get_file(id: String, state: tauri::State<'_, AppState>) -> Result<F
 pub async fn update_file(
     request: UpdateFileRequest,
     state: tauri::State<'_, AppState>,
-) -> Result<File, String> {
-    let db_guard = state.db.lock().await;
-    let pool = db_guard.as_ref().ok_or("Database not initialized")?;
+) -> Result<File, DbError> {
+    let pool = &state.pool().await?;
     db::update_file(
         pool,
         &request.id,
@@ -55,9 +125,72 @@ pub async fn update_file(
 }
 
 #[tauri::command]
-pub async fn delete_file(id: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
-    let db_guard = state.db.lock().await;
-    let pool = db_guard.as_ref().ok_or("Database not initialized")?;
+pub async fn relink_file(
+    file_id: String,
+    new_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<RelinkResult, DbError> {
+    let path = new_path.clone();
+    let (validation, hashes) = state
+        .pdf_limiter
+        .run(move || (pdf::validate_pdf(&path), pdf::file_hashes(&path)))
+        .await;
+    let pool = &state.pool().await?;
+    db::relink_file(pool, &file_id, &new_path, &validation, &hashes).await
+}
+
+#[tauri::command]
+pub async fn add_file_tag(
+    file_id: String,
+    tag: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), DbError> {
+    let pool = &state.pool().await?;
+    db::add_file_tag(pool, &file_id, &tag).await
+}
+
+#[tauri::command]
+pub async fn remove_file_tag(
+    file_id: String,
+    tag: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), DbError> {
+    let pool = &state.pool().await?;
+    db::remove_file_tag(pool, &file_id, &tag).await
+}
+
+#[tauri::command]
+pub async fn list_file_tags(
+    file_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, DbError> {
+    let pool = &state.pool().await?;
+    db::list_file_tags(pool, &file_id).await
+}
+
+#[tauri::command]
+pub async fn list_files_by_tag(
+    case_id: String,
+    tag: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<File>, DbError> {
+    let pool = &state.pool().await?;
+    db::list_files_by_tag(pool, &case_id, &tag).await
+}
+
+#[tauri::command]
+pub async fn delete_file(id: String, state: tauri::State<'_, AppState>) -> Result<(), DbError> {
+    let pool = &state.pool().await?;
     db::delete_file(pool, &id).await
 }
 
+/// Delete several files at once; all or none are removed
+#[tauri::command]
+pub async fn delete_files(
+    ids: Vec<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<u64, DbError> {
+    let pool = &state.pool().await?;
+    db::delete_files(pool, &ids).await
+}
+
@@ -5,14 +5,17 @@
 //! - file: File repository operations
 //! - entry: Artifact entry operations (linking files to cases)
 //! - pdf: PDF metadata extraction and analysis
+//! - settings: App-wide user preferences
 
 pub mod case;
 pub mod entry;
 pub mod file;
 pub mod pdf;
+pub mod settings;
 
 pub use case::*;
 pub use entry::*;
 pub use file::*;
 pub use pdf::*;
+pub use settings::*;
 
@@ -1,25 +1,344 @@
 //! PDF commands - Metadata extraction and document analysis
+//!
+//! PDF work is blocking, so every command runs it through the PDF limiter on
+//! the blocking thread pool rather than on an async worker.
+
+use base64::Engine;
 
 use crate::pdf;
-use crate::PdfMetadata;
+use crate::{AppState, PdfMetadata};
 
 #[tauri::command]
-pub async fn extract_pdf_metadata(file_path: String) -> Result<PdfMetadata, String> {
-    let metadata = pdf::extract_pdf_metadata(&file_path)?;
+pub async fn extract_pdf_metadata(
+    file_path: String,
+    password: Option<String>,
+    count_words: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<PdfMetadata, pdf::PdfError> {
+    let path = file_path.clone();
+    let metadata = state
+        .pdf_limiter
+        .run(move || {
+            pdf::extract_pdf_metadata(&path, password.as_deref(), count_words.unwrap_or(true))
+        })
+        .await?;
+    state
+        .pdf_cache
+        .record_page_count(&file_path, metadata.page_count);
     Ok(PdfMetadata {
         page_count: metadata.page_count,
         title: metadata.title,
+        author: metadata.author,
+        creation_date: metadata.creation_date,
         file_size: metadata.file_size,
+        word_count: metadata.word_count,
+        char_count: metadata.char_count,
     })
 }
 
+/// Page count of a PDF, served from the session cache when the file is unchanged
+#[tauri::command]
+pub async fn get_page_count(
+    file_path: String,
+    password: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<usize, pdf::PdfError> {
+    if let Some(page_count) = state.pdf_cache.cached_page_count(&file_path) {
+        return Ok(page_count);
+    }
+    let cache = state.pdf_cache.clone();
+    state
+        .pdf_limiter
+        .run(move || cache.page_count(&file_path, password.as_deref()))
+        .await
+}
+
+#[tauri::command]
+pub async fn extract_document_info(
+    file_path: String,
+    password: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<pdf::ExtractedDocumentInfo, pdf::PdfError> {
+    state
+        .pdf_limiter
+        .run(move || pdf::extract_document_info(&file_path, password.as_deref()))
+        .await
+}
+
+/// Detect the document type with the user's rules on top of the built-in ones
+#[tauri::command]
+pub async fn extract_document_info_with_rules(
+    file_path: String,
+    password: Option<String>,
+    rules: Vec<pdf::TypeRule>,
+    state: tauri::State<'_, AppState>,
+) -> Result<pdf::ExtractedDocumentInfo, pdf::PdfError> {
+    state
+        .pdf_limiter
+        .run(move || {
+            pdf::extract_document_info_with_rules(
+                &file_path,
+                password.as_deref(),
+                &pdf::merge_type_rules(rules),
+            )
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn get_default_type_rules() -> Vec<pdf::TypeRule> {
+    pdf::default_type_rules()
+}
+
+#[tauri::command]
+pub async fn generate_auto_description(
+    file_path: String,
+    password: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, pdf::PdfError> {
+    state
+        .pdf_limiter
+        .run(move || pdf::generate_auto_description(&file_path, password.as_deref()))
+        .await
+}
+
 #[tauri::command]
-pub async fn extract_document_info(file_path: String) -> Result<pdf::ExtractedDocumentInfo, String> {
-    pdf::extract_document_info(&file_path)
+pub async fn detect_optional_content(
+    file_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<usize>, String> {
+    state
+        .pdf_limiter
+        .run(move || pdf::detect_optional_content(&file_path))
+        .await
 }
 
 #[tauri::command]
-pub async fn generate_auto_description(file_path: String) -> Result<String, String> {
-    pdf::generate_auto_description(&file_path)
+pub async fn flatten_optional_content(
+    input_path: String,
+    output_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<usize>, String> {
+    state
+        .pdf_limiter
+        .run(move || pdf::flatten_optional_content(&input_path, &output_path))
+        .await
 }
 
+#[tauri::command]
+pub async fn self_check_pdf(
+    pdf_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<pdf::SelfCheckResult, String> {
+    Ok(state
+        .pdf_limiter
+        .run(move || pdf::self_check(&pdf_path))
+        .await)
+}
+
+#[tauri::command]
+pub async fn generate_endorsement_page(
+    info: pdf::EndorsementInfo,
+    total_pages: usize,
+    output_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    state
+        .pdf_limiter
+        .run(move || pdf::generate_endorsement_page(&info, total_pages, &output_path))
+        .await
+}
+
+#[tauri::command]
+pub async fn append_endorsement_page(
+    bundle_path: String,
+    info: pdf::EndorsementInfo,
+    numbered: bool,
+    output_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<usize, String> {
+    state
+        .pdf_limiter
+        .run(move || pdf::append_endorsement_page(&bundle_path, &info, numbered, &output_path))
+        .await
+}
+
+#[tauri::command]
+pub async fn generate_cover_pdf(
+    info: pdf::CoverInfo,
+    output_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    state
+        .pdf_limiter
+        .run(move || pdf::generate_cover_pdf(&info, &output_path))
+        .await
+}
+
+#[tauri::command]
+pub async fn prepend_cover_page(
+    bundle_path: String,
+    info: pdf::CoverInfo,
+    output_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<usize, String> {
+    state
+        .pdf_limiter
+        .run(move || pdf::prepend_cover_page(&bundle_path, &info, &output_path))
+        .await
+}
+
+#[tauri::command]
+pub async fn generate_certificate(
+    bundle_path: String,
+    info: pdf::CertificateInfo,
+    position: pdf::CertificatePosition,
+    output_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<usize, String> {
+    state
+        .pdf_limiter
+        .run(move || pdf::generate_certificate(&bundle_path, &info, position, &output_path))
+        .await
+}
+
+#[tauri::command]
+pub async fn extract_text_with_positions(
+    file_path: String,
+    page_index: usize,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<pdf::TextSpan>, String> {
+    state
+        .pdf_limiter
+        .run(move || pdf::extract_text_with_positions(&file_path, page_index))
+        .await
+}
+
+#[tauri::command]
+pub async fn detect_stamp_collision(
+    file_path: String,
+    corner: pdf::StampCorner,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<usize>, String> {
+    state
+        .pdf_limiter
+        .run(move || pdf::detect_stamp_collision(&file_path, corner))
+        .await
+}
+
+/// Returns the number the next file's Bates numbering should start from
+#[tauri::command]
+pub async fn inject_bates_numbers(
+    input_path: String,
+    output_path: String,
+    start: u64,
+    style: pdf::BatesStyle,
+    state: tauri::State<'_, AppState>,
+) -> Result<u64, String> {
+    state
+        .pdf_limiter
+        .run(move || pdf::inject_bates_numbers(&input_path, &output_path, start, &style))
+        .await
+}
+
+/// Stamp the first page of an exhibit; `corner` defaults to bottom right
+#[tauri::command]
+pub async fn inject_exhibit_stamp(
+    input_path: String,
+    output_path: String,
+    exhibit_label: String,
+    affidavit_text: String,
+    corner: Option<pdf::StampCorner>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    state
+        .pdf_limiter
+        .run(move || {
+            pdf::inject_exhibit_stamp(
+                &input_path,
+                &output_path,
+                &exhibit_label,
+                &affidavit_text,
+                corner.unwrap_or(pdf::StampCorner::BottomRight),
+            )
+        })
+        .await
+}
+
+/// PNG thumbnail of a page, base64 encoded
+#[tauri::command]
+pub async fn render_page_thumbnail(
+    file_path: String,
+    page_index: usize,
+    max_px: u32,
+    password: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, pdf::PdfError> {
+    let png = state
+        .pdf_limiter
+        .run(move || {
+            pdf::render_page_thumbnail(&file_path, password.as_deref(), page_index, max_px)
+        })
+        .await?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(png))
+}
+
+/// Text of a scanned PDF recovered by OCR (needs the `ocr` feature)
+#[tauri::command]
+pub async fn ocr_document(
+    file_path: String,
+    password: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, pdf::PdfError> {
+    state
+        .pdf_limiter
+        .run(move || pdf::extract_text_ocr(&file_path, password.as_deref()))
+        .await
+}
+
+#[tauri::command]
+pub async fn write_name_index_pdf(
+    entries: Vec<pdf::NameIndexEntry>,
+    output_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<usize, String> {
+    state
+        .pdf_limiter
+        .run(move || pdf::write_name_index_pdf(&entries, &output_path))
+        .await
+}
+
+#[tauri::command]
+pub async fn page_phash(
+    file_path: String,
+    page_index: usize,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    state
+        .pdf_limiter
+        .run(move || pdf::page_phash(&file_path, page_index))
+        .await
+        .map(|hash| format!("{:016x}", hash))
+}
+
+#[tauri::command]
+pub async fn validate_pdf(
+    file_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<pdf::PdfValidation, String> {
+    Ok(state
+        .pdf_limiter
+        .run(move || pdf::validate_pdf(&file_path))
+        .await)
+}
+
+#[tauri::command]
+pub async fn validate_folder(
+    folder_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<pdf::PdfValidation>, String> {
+    state
+        .pdf_limiter
+        .run(move || pdf::validate_folder(&folder_path))
+        .await
+}
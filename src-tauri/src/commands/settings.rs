@@ -0,0 +1,40 @@
+//! Settings commands - App-wide user preferences
+
+use crate::db::{self, DbError};
+use crate::{AppState, DescriptionLimit};
+
+#[tauri::command]
+pub async fn get_description_limit(
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<DescriptionLimit>, DbError> {
+    let pool = &state.pool().await?;
+    db::get_description_limit(pool).await
+}
+
+#[tauri::command]
+pub async fn set_description_limit(
+    limit: Option<DescriptionLimit>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), DbError> {
+    let pool = &state.pool().await?;
+    db::set_description_limit(pool, limit.as_ref()).await
+}
+
+/// Copy the live database into `dest_dir` under a timestamped name
+#[tauri::command]
+pub async fn backup_database(
+    dest_dir: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, DbError> {
+    let pool = &state.pool().await?;
+    db::backup_database(pool, std::path::Path::new(&dest_dir)).await
+}
+
+/// Limit concurrent heavy PDF operations (held in memory for this session)
+#[tauri::command]
+pub async fn set_pdf_concurrency(
+    limit: usize,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    state.pdf_limiter.set_limit(limit)
+}
@@ -0,0 +1,534 @@
+//! Case archives for backup and sharing
+//!
+//! An archive is a ZIP with `case.json` (the case, its files, entries,
+//! sections and bundle draft) and each referenced PDF under `files/`,
+//! named by file id. Importing creates a new case with fresh ids and
+//! copies the PDFs out of the archive.
+//!
+//! Reading and writing the ZIP is synchronous and kept apart from the
+//! queries, so commands can run it on the blocking pool.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+
+use super::duplicate::remap_ids;
+use super::{
+    list_case_sections, list_entries, list_file_tags, list_files, load_bundle_draft, DbError,
+};
+use crate::{ArtifactEntry, Case, CaseExportResult, CaseSection, File};
+
+/// Bumped when the layout of `case.json` changes incompatibly
+const ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchivedFile {
+    #[serde(flatten)]
+    file: File,
+    tags: Vec<String>,
+    /// Path inside the archive, `None` when the PDF was missing at export
+    archive_path: Option<String>,
+}
+
+/// The contents of `case.json`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CaseArchive {
+    version: u32,
+    case: Case,
+    files: Vec<ArchivedFile>,
+    entries: Vec<ArtifactEntry>,
+    sections: Vec<CaseSection>,
+    bundle_draft: Option<String>,
+    warnings: Vec<String>,
+}
+
+/// Load a case and everything attached to it for export
+pub async fn load_case_archive(pool: &Pool<Sqlite>, case_id: &str) -> Result<CaseArchive, DbError> {
+    let case = sqlx::query_as::<_, Case>(
+        "SELECT id, name, case_type, content_json, created_at, updated_at FROM cases
+         WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(case_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(DbError::query("Failed to load case"))?
+    .ok_or_else(|| DbError::not_found("Case", case_id))?;
+
+    let mut files = Vec::new();
+    for file in list_files(pool, case_id).await? {
+        let tags = list_file_tags(pool, &file.id).await?;
+        files.push(ArchivedFile {
+            file,
+            tags,
+            archive_path: None,
+        });
+    }
+
+    Ok(CaseArchive {
+        version: ARCHIVE_VERSION,
+        case,
+        files,
+        entries: list_entries(pool, case_id).await?,
+        sections: list_case_sections(pool, case_id).await?,
+        bundle_draft: load_bundle_draft(pool, case_id).await?,
+        warnings: Vec::new(),
+    })
+}
+
+/// Write a loaded case and its PDFs to a ZIP at `output_path`. Files missing
+/// from disk are listed as warnings instead of failing the export.
+pub fn write_case_zip(
+    mut archive: CaseArchive,
+    output_path: &str,
+) -> Result<CaseExportResult, DbError> {
+    let output =
+        std::fs::File::create(output_path).map_err(DbError::io("Failed to create archive"))?;
+    let mut zip = zip::ZipWriter::new(output);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    let mut warnings = Vec::new();
+    for archived in &mut archive.files {
+        let file = &archived.file;
+        archived.archive_path = match std::fs::read(&file.path) {
+            Ok(bytes) => {
+                let name = format!("files/{}.pdf", file.id);
+                zip.start_file(name.as_str(), options)
+                    .map_err(DbError::io("Failed to write archive"))?;
+                zip.write_all(&bytes)
+                    .map_err(DbError::io("Failed to write archive"))?;
+                Some(name)
+            }
+            Err(e) => {
+                warnings.push(format!(
+                    "{} not exported ({}): {}",
+                    file.original_name, file.path, e
+                ));
+                None
+            }
+        };
+    }
+    archive.warnings = warnings.clone();
+
+    let json = serde_json::to_vec_pretty(&archive)
+        .map_err(|e| DbError::Invalid(format!("Failed to encode case: {}", e)))?;
+    zip.start_file("case.json", options)
+        .map_err(DbError::io("Failed to write archive"))?;
+    zip.write_all(&json)
+        .map_err(DbError::io("Failed to write archive"))?;
+    zip.finish()
+        .map_err(DbError::io("Failed to write archive"))?;
+
+    Ok(CaseExportResult {
+        file_count: archive
+            .files
+            .iter()
+            .filter(|f| f.archive_path.is_some())
+            .count(),
+        warnings,
+    })
+}
+
+fn read_archive(zip_path: &str) -> Result<(zip::ZipArchive<std::fs::File>, CaseArchive), DbError> {
+    let file = std::fs::File::open(zip_path).map_err(DbError::io("Failed to open archive"))?;
+    let mut zip = zip::ZipArchive::new(file).map_err(DbError::io("Failed to read archive"))?;
+    let mut json = String::new();
+    zip.by_name("case.json")
+        .map_err(|_| DbError::Invalid("Not a case archive: case.json is missing".to_string()))?
+        .read_to_string(&mut json)
+        .map_err(DbError::io("Failed to read case.json"))?;
+    let archive: CaseArchive = serde_json::from_str(&json)
+        .map_err(|e| DbError::Invalid(format!("Invalid case.json: {}", e)))?;
+    if archive.version > ARCHIVE_VERSION {
+        return Err(DbError::Invalid(format!(
+            "Archive version {} is newer than this app supports",
+            archive.version
+        )));
+    }
+    Ok((zip, archive))
+}
+
+/// An archive whose PDFs have been copied out, ready to insert as a new case
+pub struct UnpackedArchive {
+    archive: CaseArchive,
+    case_id: String,
+    /// Where the PDFs were copied
+    case_dir: PathBuf,
+    /// New id and path of each archived file, in archive order
+    file_paths: Vec<(String, String)>,
+}
+
+impl UnpackedArchive {
+    /// Remove the copied PDFs after a failed insert
+    pub fn discard(&self) {
+        std::fs::remove_dir_all(&self.case_dir).ok();
+    }
+}
+
+/// Read an archive written by [`write_case_zip`] and copy its PDFs into
+/// `files_dir/<new case id>/`. Files that were missing at export keep their
+/// original path so they can be relinked.
+pub fn unpack_case_zip(zip_path: &str, files_dir: &Path) -> Result<UnpackedArchive, DbError> {
+    let (mut zip, archive) = read_archive(zip_path)?;
+    let case_id = uuid::Uuid::new_v4().to_string();
+    let case_dir = files_dir.join(&case_id);
+
+    let mut file_paths = Vec::new();
+    for archived in &archive.files {
+        let new_id = uuid::Uuid::new_v4().to_string();
+        let path = match &archived.archive_path {
+            Some(archive_path) => {
+                let mut bytes = Vec::new();
+                zip.by_name(archive_path)
+                    .map_err(|_| DbError::Invalid(format!("Archive is missing {}", archive_path)))?
+                    .read_to_end(&mut bytes)
+                    .map_err(DbError::io("Failed to read archive"))?;
+                let path = case_dir.join(format!("{}.pdf", new_id));
+                std::fs::create_dir_all(&case_dir)
+                    .map_err(DbError::io("Failed to create import folder"))?;
+                std::fs::write(&path, bytes).map_err(DbError::io("Failed to copy file"))?;
+                path.to_string_lossy().into_owned()
+            }
+            None => archived.file.path.clone(),
+        };
+        file_paths.push((new_id, path));
+    }
+
+    Ok(UnpackedArchive {
+        archive,
+        case_id,
+        case_dir,
+        file_paths,
+    })
+}
+
+/// Insert an unpacked archive as a new case, using the new file ids and paths
+pub async fn insert_case_archive(
+    pool: &Pool<Sqlite>,
+    unpacked: &UnpackedArchive,
+) -> Result<Case, DbError> {
+    let UnpackedArchive {
+        archive,
+        case_id,
+        file_paths,
+        ..
+    } = unpacked;
+    let case_id = case_id.as_str();
+    let file_ids: HashMap<String, String> = archive
+        .files
+        .iter()
+        .zip(file_paths)
+        .map(|(archived, (new_id, _))| (archived.file.id.clone(), new_id.clone()))
+        .collect();
+    let now = chrono::Utc::now().to_rfc3339();
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(DbError::query("Failed to import case"))?;
+    sqlx::query(
+        "INSERT INTO cases (id, name, case_type, content_json, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(case_id)
+    .bind(&archive.case.name)
+    .bind(&archive.case.case_type)
+    .bind(archive.case.content_json.as_deref().map(|content| remap_ids(content, &file_ids)))
+    .bind(&now)
+    .bind(&now)
+    .execute(&mut *tx)
+    .await
+    .map_err(DbError::query("Failed to import case"))?;
+
+    for (archived, (new_id, path)) in archive.files.iter().zip(file_paths) {
+        let file = &archived.file;
+        sqlx::query(
+            "INSERT INTO files (id, case_id, path, original_name, page_count, metadata_json, content_hash, visual_hash, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(new_id)
+        .bind(case_id)
+        .bind(path)
+        .bind(&file.original_name)
+        .bind(file.page_count)
+        .bind(&file.metadata_json)
+        .bind(&file.content_hash)
+        .bind(&file.visual_hash)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(DbError::query("Failed to import file"))?;
+        for tag in &archived.tags {
+            sqlx::query(
+                "INSERT OR IGNORE INTO file_tags (file_id, tag, created_at) VALUES (?, ?, ?)",
+            )
+            .bind(new_id)
+            .bind(tag)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await
+            .map_err(DbError::query("Failed to import file tag"))?;
+        }
+    }
+
+    for entry in &archive.entries {
+        sqlx::query(
+            "INSERT INTO artifact_entries (id, case_id, sequence_order, row_type, file_id, config_json, label_override, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(case_id)
+        .bind(entry.sequence_order)
+        .bind(&entry.row_type)
+        .bind(entry.file_id.as_ref().and_then(|file_id| file_ids.get(file_id)))
+        .bind(entry.config_json.as_deref().map(|config| remap_ids(config, &file_ids)))
+        .bind(&entry.label_override)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(DbError::query("Failed to import entry"))?;
+    }
+
+    for section in &archive.sections {
+        sqlx::query(
+            "INSERT INTO case_sections (id, case_id, position, heading, format, content, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(case_id)
+        .bind(section.position)
+        .bind(&section.heading)
+        .bind(&section.format)
+        .bind(remap_ids(&section.content, &file_ids))
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(DbError::query("Failed to import section"))?;
+    }
+
+    if let Some(draft) = &archive.bundle_draft {
+        sqlx::query("INSERT INTO bundle_drafts (case_id, draft_json, updated_at) VALUES (?, ?, ?)")
+            .bind(case_id)
+            .bind(remap_ids(draft, &file_ids))
+            .bind(&now)
+            .execute(&mut *tx)
+            .await
+            .map_err(DbError::query("Failed to import bundle draft"))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(DbError::query("Failed to import case"))?;
+
+    sqlx::query_as::<_, Case>(
+        "SELECT id, name, case_type, content_json, created_at, updated_at FROM cases WHERE id = ?",
+    )
+    .bind(case_id)
+    .fetch_one(pool)
+    .await
+    .map_err(DbError::query("Failed to load case"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::queries::{create_case, create_entry, create_file};
+    use crate::db::schema::run_migrations;
+    use crate::pdf::fixtures::{temp_pdf_path, write_text_pdf};
+    use crate::pdf::FileHashes;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> Pool<Sqlite> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+
+        run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_export_contains_json_and_files() {
+        let pool = setup_test_db().await;
+        let pdf = temp_pdf_path("archive-source");
+        write_text_pdf(&pdf, &["Letter of demand"]);
+        let case = create_case(&pool, "Tan v Lim", "bundle", None)
+            .await
+            .unwrap();
+        let file = create_file(
+            &pool,
+            &case.id,
+            pdf.to_str().unwrap(),
+            "letter.pdf",
+            Some(1),
+            None,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+        create_file(
+            &pool,
+            &case.id,
+            "/missing/gone.pdf",
+            "gone.pdf",
+            None,
+            None,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+        create_entry(&pool, &case.id, 0, "file", Some(&file.id), None, None)
+            .await
+            .unwrap();
+
+        let output =
+            std::env::temp_dir().join(format!("casepilot-export-{}.zip", uuid::Uuid::new_v4()));
+        let archive = load_case_archive(&pool, &case.id).await.unwrap();
+        let result = write_case_zip(archive, output.to_str().unwrap()).unwrap();
+        let mut zip = zip::ZipArchive::new(std::fs::File::open(&output).unwrap()).unwrap();
+        let mut json = String::new();
+        zip.by_name("case.json")
+            .unwrap()
+            .read_to_string(&mut json)
+            .unwrap();
+        let mut bytes = Vec::new();
+        zip.by_name(&format!("files/{}.pdf", file.id))
+            .unwrap()
+            .read_to_end(&mut bytes)
+            .unwrap();
+        let original = std::fs::read(&pdf).unwrap();
+        std::fs::remove_file(&pdf).ok();
+        std::fs::remove_file(&output).ok();
+
+        assert_eq!(result.file_count, 1);
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].starts_with("gone.pdf not exported"));
+        assert_eq!(bytes, original);
+        assert_eq!(zip.len(), 2);
+
+        let archive: CaseArchive = serde_json::from_str(&json).unwrap();
+        assert_eq!(archive.case.name, "Tan v Lim");
+        assert_eq!(archive.files.len(), 2);
+        assert_eq!(archive.entries.len(), 1);
+        assert_eq!(archive.warnings, result.warnings);
+    }
+
+    #[tokio::test]
+    async fn test_import_round_trip() {
+        let pool = setup_test_db().await;
+        let pdf = temp_pdf_path("archive-round-trip");
+        write_text_pdf(&pdf, &["Invoice No. 42"]);
+        let case = create_case(&pool, "Re Ong", "affidavit", None)
+            .await
+            .unwrap();
+        let file = create_file(
+            &pool,
+            &case.id,
+            pdf.to_str().unwrap(),
+            "invoice.pdf",
+            Some(1),
+            None,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+        create_entry(
+            &pool,
+            &case.id,
+            0,
+            "component",
+            None,
+            Some(r#"{"kind":"cover"}"#),
+            None,
+        )
+        .await
+        .unwrap();
+        create_entry(
+            &pool,
+            &case.id,
+            1,
+            "file",
+            Some(&file.id),
+            None,
+            Some("Tab 1"),
+        )
+        .await
+        .unwrap();
+        let content = format!(r#"{{"content":"<p data-file-id=\"{}\">A</p>"}}"#, file.id);
+        sqlx::query("UPDATE cases SET content_json = ? WHERE id = ?")
+            .bind(&content)
+            .bind(&case.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let output =
+            std::env::temp_dir().join(format!("casepilot-round-trip-{}.zip", uuid::Uuid::new_v4()));
+        let files_dir =
+            std::env::temp_dir().join(format!("casepilot-imports-{}", uuid::Uuid::new_v4()));
+        let archive = load_case_archive(&pool, &case.id).await.unwrap();
+        write_case_zip(archive, output.to_str().unwrap()).unwrap();
+        let unpacked = unpack_case_zip(output.to_str().unwrap(), &files_dir).unwrap();
+        let imported = insert_case_archive(&pool, &unpacked).await.unwrap();
+
+        let files = list_files(&pool, &imported.id).await.unwrap();
+        let copied = std::fs::read(&files[0].path).unwrap();
+        let original = std::fs::read(&pdf).unwrap();
+        std::fs::remove_file(&pdf).ok();
+        std::fs::remove_file(&output).ok();
+        std::fs::remove_dir_all(&files_dir).ok();
+
+        assert_ne!(imported.id, case.id);
+        assert_eq!(imported.name, "Re Ong");
+        assert_eq!(imported.case_type, "affidavit");
+        assert_eq!(files.len(), 1);
+        assert_ne!(files[0].id, file.id);
+        assert!(files[0].path.starts_with(files_dir.to_str().unwrap()));
+        assert_eq!(copied, original);
+        assert!(imported.content_json.unwrap().contains(&files[0].id));
+
+        let summary = |entries: Vec<ArtifactEntry>| -> Vec<_> {
+            entries
+                .into_iter()
+                .map(|e| {
+                    (
+                        e.sequence_order,
+                        e.row_type,
+                        e.config_json,
+                        e.label_override,
+                    )
+                })
+                .collect()
+        };
+        let entries = list_entries(&pool, &imported.id).await.unwrap();
+        assert_eq!(entries[1].file_id.as_deref(), Some(files[0].id.as_str()));
+        assert_eq!(
+            summary(entries),
+            summary(list_entries(&pool, &case.id).await.unwrap())
+        );
+    }
+
+    #[test]
+    fn test_import_rejects_archive_without_case_json() {
+        let path =
+            std::env::temp_dir().join(format!("casepilot-not-a-case-{}.zip", uuid::Uuid::new_v4()));
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+        zip.start_file("notes.txt", SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(b"hello").unwrap();
+        zip.finish().unwrap();
+
+        let result = unpack_case_zip(path.to_str().unwrap(), &std::env::temp_dir());
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "Not a case archive: case.json is missing"
+        );
+    }
+}
@@ -0,0 +1,640 @@
+//! Case content_json integrity checks
+
+use serde_json::Value;
+use sqlx::{Pool, Sqlite};
+use std::collections::{HashMap, HashSet};
+
+use super::DbError;
+use crate::ContentValidationResult;
+
+/// Ids referenced from a case's editor content
+#[derive(Debug, Default, Clone)]
+struct ContentReferences {
+    file_ids: Vec<String>,
+    case_ids: Vec<String>,
+}
+
+impl ContentReferences {
+    fn push_file(&mut self, id: &str) {
+        if !self.file_ids.iter().any(|f| f == id) {
+            self.file_ids.push(id.to_string());
+        }
+    }
+
+    fn push_case(&mut self, id: &str) {
+        if !self.case_ids.iter().any(|c| c == id) {
+            self.case_ids.push(id.to_string());
+        }
+    }
+}
+
+/// Parse `content_json` and collect every file/case id it references.
+///
+/// The stored shape is `{ "content": <TipTap JSON | TipTap JSON string | HTML>, ... }`,
+/// mirroring `extractExhibitFileIds` on the frontend.
+fn collect_references(content_json: &str) -> Result<ContentReferences, String> {
+    let root: Value = serde_json::from_str(content_json)
+        .map_err(|e| format!("content_json is not valid JSON: {}", e))?;
+
+    let mut refs = ContentReferences::default();
+    collect_from_value(&root, &mut refs);
+    Ok(refs)
+}
+
+fn collect_from_value(value: &Value, refs: &mut ContentReferences) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::Object(attrs)) = map.get("attrs") {
+                if let Some(Value::String(file_id)) = attrs.get("fileId") {
+                    refs.push_file(file_id);
+                }
+                if let Some(Value::String(case_id)) = attrs.get("caseId") {
+                    refs.push_case(case_id);
+                }
+            }
+
+            for (key, child) in map {
+                match child {
+                    // Editor content is sometimes stored as a serialized string
+                    Value::String(s) if key == "content" => {
+                        match serde_json::from_str::<Value>(s) {
+                            Ok(parsed) => collect_from_value(&parsed, refs),
+                            Err(_) => collect_from_html(s, refs),
+                        }
+                    }
+                    _ => collect_from_value(child, refs),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_from_value(item, refs);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn collect_from_html(html: &str, refs: &mut ContentReferences) {
+    for (attr, is_file) in [("data-file-id=\"", true), ("data-case-id=\"", false)] {
+        let mut rest = html;
+        while let Some(idx) = rest.find(attr) {
+            rest = &rest[idx + attr.len()..];
+            let Some(end) = rest.find('"') else {
+                break;
+            };
+            if is_file {
+                refs.push_file(&rest[..end]);
+            } else {
+                refs.push_case(&rest[..end]);
+            }
+            rest = &rest[end..];
+        }
+    }
+}
+
+/// Validate the references stored in a case's `content_json`.
+///
+/// Checks that every exhibit `fileId` exists in the case's repository, that every
+/// nested `caseId` points at an existing case, and that following case references
+/// never leads back to a case already on the current path.
+pub async fn validate_content_json(
+    pool: &Pool<Sqlite>,
+    case_id: &str,
+) -> Result<ContentValidationResult, DbError> {
+    let mut issues: Vec<String> = Vec::new();
+    let mut cache: HashMap<String, Option<ContentReferences>> = HashMap::new();
+    let mut checked_files: HashSet<String> = HashSet::new();
+
+    // Depth-first walk over case references, carrying the path to spot cycles
+    let mut stack: Vec<(String, Vec<String>)> = vec![(case_id.to_string(), Vec::new())];
+
+    while let Some((current, path)) = stack.pop() {
+        if path.contains(&current) {
+            let issue = format!(
+                "Circular case reference: {} -> {}",
+                path.join(" -> "),
+                current
+            );
+            if !issues.contains(&issue) {
+                issues.push(issue);
+            }
+            continue;
+        }
+
+        if !cache.contains_key(&current) {
+            let row: Option<Option<String>> =
+                sqlx::query_scalar("SELECT content_json FROM cases WHERE id = ?")
+                    .bind(&current)
+                    .fetch_optional(pool)
+                    .await
+                    .map_err(DbError::query("Failed to load case content"))?;
+
+            let refs = match row {
+                None if path.is_empty() => return Err(DbError::not_found("Case", &current)),
+                None => {
+                    issues.push(format!(
+                        "Dangling case reference in case {}: {}",
+                        path.last().map(String::as_str).unwrap_or_default(),
+                        current
+                    ));
+                    None
+                }
+                Some(None) => Some(ContentReferences::default()),
+                Some(Some(json)) => match collect_references(&json) {
+                    Ok(refs) => Some(refs),
+                    Err(e) => {
+                        issues.push(format!("Case {}: {}", current, e));
+                        None
+                    }
+                },
+            };
+            cache.insert(current.clone(), refs);
+        }
+
+        let Some(refs) = cache.get(&current).cloned().flatten() else {
+            continue;
+        };
+
+        if checked_files.insert(current.clone()) {
+            for file_id in &refs.file_ids {
+                let exists: i32 =
+                    sqlx::query_scalar("SELECT COUNT(*) FROM files WHERE id = ? AND case_id = ?")
+                        .bind(file_id)
+                        .bind(&current)
+                        .fetch_one(pool)
+                        .await
+                        .map_err(DbError::query("Failed to check file reference"))?;
+                if exists == 0 {
+                    issues.push(format!(
+                        "Dangling file reference in case {}: {}",
+                        current, file_id
+                    ));
+                }
+            }
+        }
+
+        let mut next_path = path;
+        next_path.push(current);
+        for referenced in refs.case_ids.iter().rev() {
+            stack.push((referenced.clone(), next_path.clone()));
+        }
+    }
+
+    Ok(ContentValidationResult {
+        ok: issues.is_empty(),
+        issues,
+    })
+}
+
+/// Strip byte-order marks and convert CRLF / lone CR line endings to LF
+fn normalize_text(text: &str) -> String {
+    text.replace('\u{FEFF}', "")
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+}
+
+/// Decode the JSON escape at the start of `text` (just after the opening
+/// backslash), returning the code unit it stands for and its length
+fn parse_escape(text: &str) -> (Option<u32>, usize) {
+    match text.chars().next() {
+        Some('u') => match text.get(1..5).map(|hex| u32::from_str_radix(hex, 16)) {
+            Some(Ok(code)) => (Some(code), 5),
+            _ => (None, 1),
+        },
+        Some('r') => (Some(0x0D), 1),
+        Some('n') => (Some(0x0A), 1),
+        Some(c) => (None, c.len_utf8()),
+        None => (None, 0),
+    }
+}
+
+/// Apply [`normalize_text`] to escape sequences inside JSON string literals.
+///
+/// Works on the text rather than a parsed `Value` so key order and
+/// formatting survive, and unchanged content comes back byte for byte.
+fn normalize_json_escapes(json: &str) -> String {
+    let mut out = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut rest = json;
+
+    while let Some(c) = rest.chars().next() {
+        rest = &rest[c.len_utf8()..];
+        if !in_string || c != '\\' {
+            in_string ^= c == '"';
+            out.push(c);
+            continue;
+        }
+
+        let (code, len) = parse_escape(rest);
+        let escape = &rest[..len];
+        rest = &rest[len..];
+        match code {
+            Some(0xFEFF) => {}
+            Some(0x0D) => {
+                out.push_str("\\n");
+                if let Some(after) = rest.strip_prefix('\\') {
+                    if let (Some(0x0A), len) = parse_escape(after) {
+                        rest = &after[len..];
+                    }
+                }
+            }
+            _ => {
+                out.push('\\');
+                out.push_str(escape);
+            }
+        }
+    }
+    out
+}
+
+/// Normalize a case's stored content: LF line endings, no BOMs, valid UTF-8.
+///
+/// Pasted content can carry CRLF endings (raw or escaped inside JSON strings),
+/// byte-order marks and invalid byte sequences that later break export.
+/// Returns whether the stored content was rewritten.
+pub async fn normalize_case_content(pool: &Pool<Sqlite>, id: &str) -> Result<bool, DbError> {
+    // Read as bytes so invalid UTF-8 doesn't fail decoding
+    let row: Option<Option<Vec<u8>>> =
+        sqlx::query_scalar("SELECT CAST(content_json AS BLOB) FROM cases WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .map_err(DbError::query("Failed to load case content"))?;
+
+    let bytes = match row {
+        None => return Err(DbError::not_found("Case", id)),
+        Some(None) => return Ok(false),
+        Some(Some(bytes)) => bytes,
+    };
+
+    let mut normalized = normalize_text(&String::from_utf8_lossy(&bytes));
+    if serde_json::from_str::<Value>(&normalized).is_ok() {
+        normalized = normalize_json_escapes(&normalized);
+    }
+
+    if normalized.as_bytes() == bytes.as_slice() {
+        return Ok(false);
+    }
+
+    sqlx::query("UPDATE cases SET content_json = ?, updated_at = ? WHERE id = ?")
+        .bind(&normalized)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(DbError::query("Failed to update case content"))?;
+
+    Ok(true)
+}
+
+/// Version of the content_json layout the editor currently reads and writes
+pub const CONTENT_SCHEMA_VERSION: i64 = 1;
+
+/// Upgrade a content_json document to the current layout, or `None` if it
+/// is already current.
+///
+/// Unversioned content was stored in several shapes: a bare TipTap document,
+/// bare HTML (as a JSON string or not JSON at all), or a wrapper whose
+/// `content` was a TipTap object. Version 1 is always a wrapper object with
+/// `content` holding the editor's serialized string and a `schemaVersion`.
+fn upgrade_content(content_json: &str) -> Option<String> {
+    let root = serde_json::from_str::<Value>(content_json)
+        .unwrap_or_else(|_| Value::String(content_json.to_string()));
+
+    let mut wrapper = match root {
+        Value::Object(map) if map.get("type").and_then(Value::as_str) == Some("doc") => {
+            let mut wrapper = serde_json::Map::new();
+            wrapper.insert("content".to_string(), Value::Object(map));
+            wrapper
+        }
+        Value::Object(map) => map,
+        Value::String(html) => {
+            let mut wrapper = serde_json::Map::new();
+            wrapper.insert("content".to_string(), Value::String(html));
+            wrapper
+        }
+        // Nothing recognisable to upgrade; leave it for validation to report
+        _ => return None,
+    };
+
+    let version = wrapper
+        .get("schemaVersion")
+        .and_then(Value::as_i64)
+        .unwrap_or(0);
+    if version >= CONTENT_SCHEMA_VERSION {
+        return None;
+    }
+
+    if let Some(content) = wrapper.get_mut("content") {
+        if content.is_object() || content.is_array() {
+            *content = Value::String(content.to_string());
+        }
+    }
+    wrapper.insert(
+        "schemaVersion".to_string(),
+        Value::from(CONTENT_SCHEMA_VERSION),
+    );
+    Some(Value::Object(wrapper).to_string())
+}
+
+/// Upgrade a case's stored content to the current schema version.
+/// Returns whether the stored content was rewritten; re-running is a no-op.
+pub async fn migrate_case_content(pool: &Pool<Sqlite>, id: &str) -> Result<bool, DbError> {
+    let row: Option<Option<String>> =
+        sqlx::query_scalar("SELECT content_json FROM cases WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .map_err(DbError::query("Failed to load case content"))?;
+
+    let Some(upgraded) = row
+        .ok_or_else(|| DbError::not_found("Case", id))?
+        .and_then(|content_json| upgrade_content(&content_json))
+    else {
+        return Ok(false);
+    };
+
+    sqlx::query("UPDATE cases SET content_json = ?, updated_at = ? WHERE id = ?")
+        .bind(&upgraded)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(DbError::query("Failed to update case content"))?;
+
+    Ok(true)
+}
+
+/// Upgrade every case with content, returning the ids that were rewritten
+pub async fn migrate_all_content(pool: &Pool<Sqlite>) -> Result<Vec<String>, DbError> {
+    let ids: Vec<String> =
+        sqlx::query_scalar("SELECT id FROM cases WHERE content_json IS NOT NULL ORDER BY id")
+            .fetch_all(pool)
+            .await
+            .map_err(DbError::query("Failed to list case content"))?;
+
+    let mut migrated = Vec::new();
+    for id in ids {
+        if migrate_case_content(pool, &id).await? {
+            migrated.push(id);
+        }
+    }
+    Ok(migrated)
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::queries::{create_case, create_file};
+    use crate::db::schema::run_migrations;
+    use crate::pdf::FileHashes;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> Pool<Sqlite> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+
+        run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        pool
+    }
+
+    fn exhibit_content(attrs: &str) -> String {
+        let doc = format!(
+            r#"{{"type":"doc","content":[{{"type":"paragraph","content":[{{"type":"exhibitNode","attrs":{}}}]}}]}}"#,
+            attrs
+        );
+        serde_json::json!({ "content": doc, "initials": "JT" }).to_string()
+    }
+
+    async fn set_content(pool: &Pool<Sqlite>, case_id: &str, content_json: &str) {
+        sqlx::query("UPDATE cases SET content_json = ? WHERE id = ?")
+            .bind(content_json)
+            .bind(case_id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_valid_content_json() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Affidavit of JT", "affidavit", None)
+            .await
+            .unwrap();
+        let file = create_file(
+            &pool,
+            &case.id,
+            "/path/a.pdf",
+            "a.pdf",
+            Some(1),
+            None,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+        set_content(
+            &pool,
+            &case.id,
+            &exhibit_content(&format!(r#"{{"fileId":"{}"}}"#, file.id)),
+        )
+        .await;
+
+        let result = validate_content_json(&pool, &case.id).await.unwrap();
+        assert!(result.ok, "unexpected issues: {:?}", result.issues);
+        assert!(result.issues.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dangling_file_reference() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Affidavit of JT", "affidavit", None)
+            .await
+            .unwrap();
+        set_content(&pool, &case.id, &exhibit_content(r#"{"fileId":"missing"}"#)).await;
+
+        let result = validate_content_json(&pool, &case.id).await.unwrap();
+        assert!(!result.ok);
+        assert_eq!(result.issues.len(), 1);
+        assert!(result.issues[0].contains("Dangling file reference"));
+        assert!(result.issues[0].contains("missing"));
+    }
+
+    #[tokio::test]
+    async fn test_cyclic_case_reference() {
+        let pool = setup_test_db().await;
+        let first = create_case(&pool, "First", "affidavit", None)
+            .await
+            .unwrap();
+        let second = create_case(&pool, "Second", "affidavit", None)
+            .await
+            .unwrap();
+        set_content(
+            &pool,
+            &first.id,
+            &exhibit_content(&format!(r#"{{"caseId":"{}"}}"#, second.id)),
+        )
+        .await;
+        set_content(
+            &pool,
+            &second.id,
+            &exhibit_content(&format!(r#"{{"caseId":"{}"}}"#, first.id)),
+        )
+        .await;
+
+        let result = validate_content_json(&pool, &first.id).await.unwrap();
+        assert!(!result.ok);
+        assert!(result
+            .issues
+            .iter()
+            .any(|issue| issue.starts_with("Circular case reference")));
+    }
+
+    async fn stored_content(pool: &Pool<Sqlite>, case_id: &str) -> String {
+        sqlx::query_scalar("SELECT content_json FROM cases WHERE id = ?")
+            .bind(case_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_normalize_mixed_line_endings() {
+        let pool = setup_test_db().await;
+        let content = serde_json::json!({ "content": "line one\r\nline two\rline three\n" });
+        let case = create_case(
+            &pool,
+            "Affidavit of JT",
+            "affidavit",
+            Some(&format!("{}\r\n", content)),
+        )
+        .await
+        .unwrap();
+
+        assert!(normalize_case_content(&pool, &case.id).await.unwrap());
+
+        let stored: Value = serde_json::from_str(&stored_content(&pool, &case.id).await).unwrap();
+        assert_eq!(stored["content"], "line one\nline two\nline three\n");
+        assert!(!stored_content(&pool, &case.id).await.contains('\r'));
+
+        // Already normalized content is left alone
+        assert!(!normalize_case_content(&pool, &case.id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_normalize_strips_bom() {
+        let pool = setup_test_db().await;
+        let case = create_case(
+            &pool,
+            "Affidavit of JT",
+            "affidavit",
+            Some("\u{FEFF}{\"content\":\"\u{FEFF}Hello\"}"),
+        )
+        .await
+        .unwrap();
+
+        assert!(normalize_case_content(&pool, &case.id).await.unwrap());
+        assert_eq!(
+            stored_content(&pool, &case.id).await,
+            r#"{"content":"Hello"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_normalize_keeps_key_order() {
+        let pool = setup_test_db().await;
+        let content = r#"{"initials":"JT","path":"C:\\root","content":"a\r\nb\rc\ufeff"}"#;
+        let case = create_case(&pool, "Affidavit of JT", "affidavit", Some(content))
+            .await
+            .unwrap();
+
+        assert!(normalize_case_content(&pool, &case.id).await.unwrap());
+        assert_eq!(
+            stored_content(&pool, &case.id).await,
+            r#"{"initials":"JT","path":"C:\\root","content":"a\nb\nc"}"#
+        );
+
+        // Clean content with unsorted keys and spacing is not rewritten
+        let content = r#"{ "z": 1, "content": "line\nline" }"#;
+        set_content(&pool, &case.id, content).await;
+        assert!(!normalize_case_content(&pool, &case.id).await.unwrap());
+        assert_eq!(stored_content(&pool, &case.id).await, content);
+    }
+
+    #[tokio::test]
+    async fn test_normalize_replaces_invalid_utf8() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Affidavit of JT", "affidavit", None)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE cases SET content_json = ? WHERE id = ?")
+            .bind(b"{\"content\":\"caf\xe9\"}".to_vec())
+            .bind(&case.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(normalize_case_content(&pool, &case.id).await.unwrap());
+        let stored: Value = serde_json::from_str(&stored_content(&pool, &case.id).await).unwrap();
+        assert_eq!(stored["content"], "caf\u{FFFD}");
+    }
+
+    #[tokio::test]
+    async fn test_migrate_old_content_formats() {
+        let pool = setup_test_db().await;
+        let doc = r#"{"type":"doc","content":[{"type":"paragraph"}]}"#;
+        let bare_doc = create_case(&pool, "Bare doc", "affidavit", Some(doc))
+            .await
+            .unwrap();
+        let object_content = create_case(
+            &pool,
+            "Object content",
+            "affidavit",
+            Some(&format!(r#"{{"content":{},"initials":"JT"}}"#, doc)),
+        )
+        .await
+        .unwrap();
+        let raw_html = create_case(&pool, "Raw HTML", "affidavit", Some("<p>Hello</p>"))
+            .await
+            .unwrap();
+        let empty = create_case(&pool, "Empty", "bundle", None).await.unwrap();
+
+        let mut migrated = migrate_all_content(&pool).await.unwrap();
+        migrated.sort();
+        let mut expected = vec![
+            bare_doc.id.clone(),
+            object_content.id.clone(),
+            raw_html.id.clone(),
+        ];
+        expected.sort();
+        assert_eq!(migrated, expected);
+
+        for case in [&bare_doc, &object_content] {
+            let stored: Value =
+                serde_json::from_str(&stored_content(&pool, &case.id).await).unwrap();
+            assert_eq!(stored["schemaVersion"], CONTENT_SCHEMA_VERSION);
+            let content: Value = serde_json::from_str(stored["content"].as_str().unwrap()).unwrap();
+            assert_eq!(content["type"], "doc");
+        }
+        let stored: Value =
+            serde_json::from_str(&stored_content(&pool, &object_content.id).await).unwrap();
+        assert_eq!(stored["initials"], "JT");
+        let stored: Value =
+            serde_json::from_str(&stored_content(&pool, &raw_html.id).await).unwrap();
+        assert_eq!(stored["content"], "<p>Hello</p>");
+
+        // Current content is left alone
+        assert!(migrate_all_content(&pool).await.unwrap().is_empty());
+        assert!(!migrate_case_content(&pool, &bare_doc.id).await.unwrap());
+        assert!(!migrate_case_content(&pool, &empty.id).await.unwrap());
+    }
+}
@@ -0,0 +1,319 @@
+//! Copying a case with everything attached to it
+//!
+//! Files, tags, entries, sections and the bundle draft are copied with new
+//! ids. References to the original files inside editor content, entry
+//! configs and drafts are rewritten so the copy only points at its own rows.
+
+use sqlx::{Pool, Sqlite, Transaction};
+use std::collections::HashMap;
+
+use super::DbError;
+use crate::{ArtifactEntry, Case, CaseSection, File};
+
+/// Replace every old id in `text` with its new id. Ids are UUIDs, so a plain
+/// text replacement can't hit anything else.
+pub(super) fn remap_ids(text: &str, ids: &HashMap<String, String>) -> String {
+    ids.iter()
+        .fold(text.to_string(), |text, (old, new)| text.replace(old, new))
+}
+
+/// Copy a case's files and their tags, returning old id -> new id
+async fn copy_files(
+    tx: &mut Transaction<'_, Sqlite>,
+    case_id: &str,
+    new_case_id: &str,
+    now: &str,
+) -> Result<HashMap<String, String>, DbError> {
+    let files = sqlx::query_as::<_, File>(
+        "SELECT id, case_id, path, original_name, page_count, metadata_json, content_hash, visual_hash, created_at
+         FROM files WHERE case_id = ?",
+    )
+    .bind(case_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(DbError::query("Failed to load files to copy"))?;
+
+    let mut ids = HashMap::new();
+    for file in files {
+        let new_id = uuid::Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO files (id, case_id, path, original_name, page_count, metadata_json, content_hash, visual_hash, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&new_id)
+        .bind(new_case_id)
+        .bind(&file.path)
+        .bind(&file.original_name)
+        .bind(file.page_count)
+        .bind(&file.metadata_json)
+        .bind(&file.content_hash)
+        .bind(&file.visual_hash)
+        .bind(now)
+        .execute(&mut **tx)
+        .await
+        .map_err(DbError::query("Failed to copy file"))?;
+
+        sqlx::query(
+            "INSERT INTO file_tags (file_id, tag, created_at)
+             SELECT ?, tag, created_at FROM file_tags WHERE file_id = ?",
+        )
+        .bind(&new_id)
+        .bind(&file.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(DbError::query("Failed to copy file tags"))?;
+
+        ids.insert(file.id, new_id);
+    }
+    Ok(ids)
+}
+
+/// Copy a case as a new case named `new_name`, in a single transaction
+pub async fn duplicate_case(
+    pool: &Pool<Sqlite>,
+    id: &str,
+    new_name: &str,
+) -> Result<Case, DbError> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        return Err(DbError::Invalid("Case name cannot be empty".to_string()));
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(DbError::query("Failed to duplicate case"))?;
+    let original = sqlx::query_as::<_, Case>(
+        "SELECT id, name, case_type, content_json, created_at, updated_at FROM cases
+         WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(DbError::query("Failed to load case"))?
+    .ok_or_else(|| DbError::not_found("Case", id))?;
+
+    let new_id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        "INSERT INTO cases (id, name, case_type, created_at, updated_at, last_output_dir)
+         SELECT ?, ?, case_type, ?, ?, last_output_dir FROM cases WHERE id = ?",
+    )
+    .bind(&new_id)
+    .bind(new_name)
+    .bind(&now)
+    .bind(&now)
+    .bind(id)
+    .execute(&mut *tx)
+    .await
+    .map_err(DbError::query("Failed to copy case"))?;
+
+    let file_ids = copy_files(&mut tx, id, &new_id, &now).await?;
+    let content_json = original
+        .content_json
+        .as_deref()
+        .map(|content| remap_ids(content, &file_ids));
+    sqlx::query("UPDATE cases SET content_json = ? WHERE id = ?")
+        .bind(&content_json)
+        .bind(&new_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(DbError::query("Failed to copy case content"))?;
+
+    let entries = sqlx::query_as::<_, ArtifactEntry>(
+        "SELECT id, case_id, sequence_order, row_type, file_id, config_json, label_override, created_at
+         FROM artifact_entries WHERE case_id = ?",
+    )
+    .bind(id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(DbError::query("Failed to load entries to copy"))?;
+    for entry in entries {
+        sqlx::query(
+            "INSERT INTO artifact_entries (id, case_id, sequence_order, row_type, file_id, config_json, label_override, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(&new_id)
+        .bind(entry.sequence_order)
+        .bind(&entry.row_type)
+        .bind(entry.file_id.map(|file_id| file_ids.get(&file_id).cloned().unwrap_or(file_id)))
+        .bind(entry.config_json.map(|config| remap_ids(&config, &file_ids)))
+        .bind(&entry.label_override)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(DbError::query("Failed to copy entry"))?;
+    }
+
+    let sections = sqlx::query_as::<_, CaseSection>(
+        "SELECT id, case_id, position, heading, format, content, updated_at
+         FROM case_sections WHERE case_id = ?",
+    )
+    .bind(id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(DbError::query("Failed to load sections to copy"))?;
+    for section in sections {
+        sqlx::query(
+            "INSERT INTO case_sections (id, case_id, position, heading, format, content, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(&new_id)
+        .bind(section.position)
+        .bind(&section.heading)
+        .bind(&section.format)
+        .bind(remap_ids(&section.content, &file_ids))
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(DbError::query("Failed to copy section"))?;
+    }
+
+    let draft: Option<String> =
+        sqlx::query_scalar("SELECT draft_json FROM bundle_drafts WHERE case_id = ?")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(DbError::query("Failed to load bundle draft"))?;
+    if let Some(draft) = draft {
+        sqlx::query("INSERT INTO bundle_drafts (case_id, draft_json, updated_at) VALUES (?, ?, ?)")
+            .bind(&new_id)
+            .bind(remap_ids(&draft, &file_ids))
+            .bind(&now)
+            .execute(&mut *tx)
+            .await
+            .map_err(DbError::query("Failed to copy bundle draft"))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(DbError::query("Failed to duplicate case"))?;
+
+    Ok(Case {
+        id: new_id,
+        name: new_name.to_string(),
+        case_type: original.case_type,
+        content_json,
+        created_at: now.clone(),
+        updated_at: now,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::queries::{
+        create_case, create_entry, create_file, get_file, list_entries, list_files, update_file,
+    };
+    use crate::db::schema::run_migrations;
+    use crate::pdf::FileHashes;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> Pool<Sqlite> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+
+        run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_is_independent() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Affidavit of Tan Ah Kow", "affidavit", None)
+            .await
+            .unwrap();
+        let invoice = create_file(
+            &pool,
+            &case.id,
+            "/docs/invoice.pdf",
+            "invoice.pdf",
+            Some(2),
+            None,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+        let letter = create_file(
+            &pool,
+            &case.id,
+            "/docs/letter.pdf",
+            "letter.pdf",
+            Some(1),
+            None,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+        for (order, file) in [&invoice, &letter].into_iter().enumerate() {
+            create_entry(
+                &pool,
+                &case.id,
+                order as i32,
+                "file",
+                Some(&file.id),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+        let content = format!(
+            r#"{{"content":{{"type":"doc","content":[{{"type":"exhibit","attrs":{{"fileId":"{}"}}}}]}}}}"#,
+            invoice.id
+        );
+        sqlx::query("UPDATE cases SET content_json = ? WHERE id = ?")
+            .bind(&content)
+            .bind(&case.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let copy = duplicate_case(&pool, &case.id, "Affidavit of Tan Ah Kow (copy)")
+            .await
+            .unwrap();
+        let copied_files = list_files(&pool, &copy.id).await.unwrap();
+        assert_eq!(copied_files.len(), 2);
+        assert!(copied_files
+            .iter()
+            .all(|f| f.id != invoice.id && f.id != letter.id));
+
+        // Entries and content point at the copy's files, not the original's
+        let copied_ids: Vec<&str> = copied_files.iter().map(|f| f.id.as_str()).collect();
+        let entries = list_entries(&pool, &copy.id).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(entries
+            .iter()
+            .all(|e| copied_ids.contains(&e.file_id.as_deref().unwrap())));
+        let copy_content = copy.content_json.unwrap();
+        assert!(!copy_content.contains(&invoice.id));
+
+        let copied_invoice = copied_files
+            .iter()
+            .find(|f| f.original_name == "invoice.pdf")
+            .unwrap();
+        assert!(copy_content.contains(&copied_invoice.id));
+        update_file(&pool, &copied_invoice.id, Some(9), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            get_file(&pool, &invoice.id).await.unwrap().page_count,
+            Some(2)
+        );
+        assert_eq!(list_entries(&pool, &case.id).await.unwrap().len(), 2);
+
+        assert!(matches!(
+            duplicate_case(&pool, "missing-id", "Copy")
+                .await
+                .unwrap_err(),
+            DbError::NotFound { entity: "Case", .. }
+        ));
+    }
+}
@@ -0,0 +1,106 @@
+//! Database error type
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// Errors from the database layer, sent to the frontend as `{ kind, message }`
+#[derive(Debug)]
+pub enum DbError {
+    /// The connection pool hasn't been set up yet
+    NotInitialized,
+    /// No row with the given id exists
+    NotFound { entity: &'static str, id: String },
+    /// Input rejected before it reached the database
+    Invalid(String),
+    /// A query failed; `context` says what was being attempted
+    Sqlx {
+        context: String,
+        source: sqlx::Error,
+    },
+    /// Reading or writing a file failed; `context` says what was being attempted
+    Io {
+        context: String,
+        source: std::io::Error,
+    },
+}
+
+impl DbError {
+    /// Wrap a query failure with context, for use as `.map_err(DbError::query("..."))`
+    pub fn query(context: impl Into<String>) -> impl FnOnce(sqlx::Error) -> DbError {
+        let context = context.into();
+        move |source| DbError::Sqlx { context, source }
+    }
+
+    /// Wrap a file error with context, for use as `.map_err(DbError::io("..."))`
+    pub fn io<E: Into<std::io::Error>>(context: impl Into<String>) -> impl FnOnce(E) -> DbError {
+        let context = context.into();
+        move |source| DbError::Io {
+            context,
+            source: source.into(),
+        }
+    }
+
+    pub fn not_found(entity: &'static str, id: &str) -> DbError {
+        DbError::NotFound {
+            entity,
+            id: id.to_string(),
+        }
+    }
+
+    /// Stable identifier for the frontend to branch on
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DbError::NotInitialized => "not_initialized",
+            DbError::NotFound { .. } => "not_found",
+            DbError::Invalid(_) => "invalid",
+            DbError::Sqlx { .. } => "sqlx",
+            DbError::Io { .. } => "io",
+        }
+    }
+}
+
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DbError::NotInitialized => write!(f, "Database not initialized"),
+            DbError::NotFound { entity, id } => write!(f, "{} not found: {}", entity, id),
+            DbError::Invalid(message) => write!(f, "{}", message),
+            DbError::Sqlx { context, source } => write!(f, "{}: {}", context, source),
+            DbError::Io { context, source } => write!(f, "{}: {}", context, source),
+        }
+    }
+}
+
+impl std::error::Error for DbError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DbError::Sqlx { source, .. } => Some(source),
+            DbError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for DbError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("DbError", 2)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_kind_and_message() {
+        let error = DbError::not_found("Case", "abc");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({ "kind": "not_found", "message": "Case not found: abc" })
+        );
+    }
+}
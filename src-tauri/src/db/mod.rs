@@ -3,11 +3,28 @@
 //! Schema Overview:
 //! - `cases`: Top-level container (IS an Affidavit or Bundle)
 //! - `files`: Raw PDF assets (the repository)
+//! - `file_tags`: Labels on individual files
 //! - `artifact_entries`: Polymorphic links (file | component)
+//! - `bundle_drafts`: In-progress bundle builder state per case
+//! - `app_settings`: Key/value user preferences
+//! - `case_sections`: Affidavit content split at headings
+//! - `compile_history`: Page counts and durations of past compiles
+//! - `schema_version`: Applied migration versions
 
+mod archive;
+mod backup;
+mod content;
+mod duplicate;
+mod error;
 mod queries;
 mod schema;
+mod sections;
 
+pub use archive::{insert_case_archive, load_case_archive, unpack_case_zip, write_case_zip};
+pub use backup::backup_database;
+pub use content::*;
+pub use duplicate::duplicate_case;
+pub use error::DbError;
 pub use queries::*;
 pub use schema::run_migrations;
-
+pub use sections::*;
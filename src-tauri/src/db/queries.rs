@@ -1,20 +1,61 @@
 //! Database CRUD operations
 
 use sqlx::{Pool, Sqlite};
+use std::collections::BTreeSet;
+use std::time::Duration;
 
-use crate::{ArtifactEntry, Case, File};
+use super::DbError;
+
+use crate::pdf::{FileHashes, NameIndexSource, PdfValidation};
+use crate::{
+    ArtifactEntry, Case, CasePage, CaseStats, DescriptionLimit, DescriptionLimitMode,
+    DocumentTypeCount, File, FileFilter, MetadataFieldValue, RelinkResult,
+};
 
 // ============================================================================
 // CASE CRUD
 // ============================================================================
 
-pub async fn list_cases(pool: &Pool<Sqlite>) -> Result<Vec<Case>, String> {
+/// Live cases, most recently updated first. `limit` and `offset` select a
+/// page; leaving `limit` out returns every case from `offset` on.
+pub async fn list_cases(
+    pool: &Pool<Sqlite>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<CasePage, DbError> {
+    if limit.is_some_and(|limit| limit < 0) || offset.is_some_and(|offset| offset < 0) {
+        return Err(DbError::Invalid(
+            "limit and offset cannot be negative".to_string(),
+        ));
+    }
+
+    // SQLite treats a negative LIMIT as no limit
+    let cases = sqlx::query_as::<_, Case>(
+        "SELECT id, name, case_type, content_json, created_at, updated_at FROM cases
+         WHERE deleted_at IS NULL ORDER BY updated_at DESC LIMIT ? OFFSET ?",
+    )
+    .bind(limit.unwrap_or(-1))
+    .bind(offset.unwrap_or(0))
+    .fetch_all(pool)
+    .await
+    .map_err(DbError::query("Failed to list cases"))?;
+    let total = sqlx::query_scalar("SELECT COUNT(*) FROM cases WHERE deleted_at IS NULL")
+        .fetch_one(pool)
+        .await
+        .map_err(DbError::query("Failed to count cases"))?;
+
+    Ok(CasePage { cases, total })
+}
+
+/// Cases in the trash, most recently trashed first
+pub async fn list_trashed_cases(pool: &Pool<Sqlite>) -> Result<Vec<Case>, DbError> {
     sqlx::query_as::<_, Case>(
-        "SELECT id, name, case_type, content_json, created_at, updated_at FROM cases ORDER BY updated_at DESC",
+        "SELECT id, name, case_type, content_json, created_at, updated_at FROM cases
+         WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
     )
     .fetch_all(pool)
     .await
-    .map_err(|e| format!("Failed to list cases: {}", e))
+    .map_err(DbError::query("Failed to list trashed cases"))
 }
 
 pub async fn create_case(
@@ -22,12 +63,12 @@ pub async fn create_case(
     name: &str,
     case_type: &str,
     content_json: Option<&str>,
-) -> Result<Case, String> {
+) -> Result<Case, DbError> {
     if !["affidavit", "bundle"].contains(&case_type) {
-        return Err(format!(
+        return Err(DbError::Invalid(format!(
             "Invalid case_type: {}. Must be 'affidavit' or 'bundle'",
             case_type
-        ));
+        )));
     }
 
     let id = uuid::Uuid::new_v4().to_string();
@@ -42,7 +83,7 @@ pub async fn create_case(
         .bind(&now)
         .execute(pool)
         .await
-        .map_err(|e| format!("Failed to create case: {}", e))?;
+        .map_err(DbError::query("Failed to create case"))?;
 
     Ok(Case {
         id,
@@ -54,12 +95,112 @@ pub async fn create_case(
     })
 }
 
-pub async fn delete_case(pool: &Pool<Sqlite>, id: &str) -> Result<(), String> {
-    sqlx::query("DELETE FROM cases WHERE id = ?")
+pub async fn rename_case(pool: &Pool<Sqlite>, id: &str, new_name: &str) -> Result<Case, DbError> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        return Err(DbError::Invalid("Case name cannot be empty".to_string()));
+    }
+
+    let result = sqlx::query(
+        "UPDATE cases SET name = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(new_name)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .bind(id)
+    .execute(pool)
+    .await
+    .map_err(DbError::query("Failed to rename case"))?;
+    if result.rows_affected() == 0 {
+        return Err(DbError::not_found("Case", id));
+    }
+
+    sqlx::query_as::<_, Case>(
+        "SELECT id, name, case_type, content_json, created_at, updated_at FROM cases WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_one(pool)
+    .await
+    .map_err(DbError::query("Failed to load case"))
+}
+
+/// Remember the folder a case was last compiled into
+pub async fn set_last_output_dir(
+    pool: &Pool<Sqlite>,
+    case_id: &str,
+    dir: &str,
+) -> Result<(), DbError> {
+    if !std::path::Path::new(dir).is_dir() {
+        return Err(DbError::Invalid(format!("Not a directory: {}", dir)));
+    }
+
+    let result =
+        sqlx::query("UPDATE cases SET last_output_dir = ? WHERE id = ? AND deleted_at IS NULL")
+            .bind(dir)
+            .bind(case_id)
+            .execute(pool)
+            .await
+            .map_err(DbError::query("Failed to save output directory"))?;
+    if result.rows_affected() == 0 {
+        return Err(DbError::not_found("Case", case_id));
+    }
+    Ok(())
+}
+
+/// The folder a case was last compiled into, if it still exists
+pub async fn get_last_output_dir(
+    pool: &Pool<Sqlite>,
+    case_id: &str,
+) -> Result<Option<String>, DbError> {
+    let dir: Option<String> = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT last_output_dir FROM cases WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(case_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(DbError::query("Failed to read output directory"))?
+    .ok_or_else(|| DbError::not_found("Case", case_id))?;
+
+    Ok(dir.filter(|dir| std::path::Path::new(dir).is_dir()))
+}
+
+/// Move a case to the trash. Its files and entries are kept until it's purged.
+pub async fn delete_case(pool: &Pool<Sqlite>, id: &str) -> Result<(), DbError> {
+    let result = sqlx::query("UPDATE cases SET deleted_at = COALESCE(deleted_at, ?) WHERE id = ?")
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(DbError::query("Failed to delete case"))?;
+    if result.rows_affected() == 0 {
+        return Err(DbError::not_found("Case", id));
+    }
+    Ok(())
+}
+
+/// Take a case back out of the trash
+pub async fn restore_case(pool: &Pool<Sqlite>, id: &str) -> Result<(), DbError> {
+    let result =
+        sqlx::query("UPDATE cases SET deleted_at = NULL WHERE id = ? AND deleted_at IS NOT NULL")
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(DbError::query("Failed to restore case"))?;
+    if result.rows_affected() == 0 {
+        return Err(DbError::not_found("Trashed case", id));
+    }
+    Ok(())
+}
+
+/// Permanently remove a trashed case, cascading to its files and entries
+pub async fn purge_case(pool: &Pool<Sqlite>, id: &str) -> Result<(), DbError> {
+    let result = sqlx::query("DELETE FROM cases WHERE id = ? AND deleted_at IS NOT NULL")
         .bind(id)
         .execute(pool)
         .await
-        .map_err(|e| format!("Failed to delete case: {}", e))?;
+        .map_err(DbError::query("Failed to purge case"))?;
+    if result.rows_affected() == 0 {
+        return Err(DbError::not_found("Trashed case", id));
+    }
     Ok(())
 }
 
@@ -67,15 +208,200 @@ pub async fn delete_case(pool: &Pool<Sqlite>, id: &str) -> Result<(), String> {
 // FILE CRUD
 // ============================================================================
 
-pub async fn list_files(pool: &Pool<Sqlite>, case_id: &str) -> Result<Vec<File>, String> {
+// Files belong to their case: while it is in the trash they are treated as
+// missing, like the case itself, until it is restored.
+
+pub async fn list_files(pool: &Pool<Sqlite>, case_id: &str) -> Result<Vec<File>, DbError> {
+    sqlx::query_as::<_, File>(
+        "SELECT id, case_id, path, original_name, page_count, metadata_json, content_hash, visual_hash, created_at
+         FROM files
+         WHERE case_id = ? AND case_id IN (SELECT id FROM cases WHERE deleted_at IS NULL)
+         ORDER BY created_at DESC",
+    )
+    .bind(case_id)
+    .fetch_all(pool)
+    .await
+    .map_err(DbError::query("Failed to list files"))
+}
+
+/// List a case's files matching `filter`, newest first
+pub async fn list_files_filtered(
+    pool: &Pool<Sqlite>,
+    case_id: &str,
+    filter: &FileFilter,
+) -> Result<Vec<File>, DbError> {
+    for date in [&filter.created_from, &filter.created_to]
+        .into_iter()
+        .flatten()
+    {
+        if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+            return Err(DbError::Invalid(format!(
+                "Invalid date: {}. Use YYYY-MM-DD",
+                date
+            )));
+        }
+    }
+
+    // created_at is RFC 3339, so its first ten characters are the date
+    sqlx::query_as::<_, File>(
+        "SELECT id, case_id, path, original_name, page_count, metadata_json, content_hash, visual_hash, created_at
+         FROM files
+         WHERE case_id = ?1
+           AND case_id IN (SELECT id FROM cases WHERE deleted_at IS NULL)
+           AND (?2 IS NULL OR (json_valid(metadata_json)
+                AND lower(CAST(json_extract(metadata_json, '$.document_type') AS TEXT)) = lower(?2)))
+           AND (?3 IS NULL OR substr(created_at, 1, 10) >= ?3)
+           AND (?4 IS NULL OR substr(created_at, 1, 10) <= ?4)
+         ORDER BY created_at DESC",
+    )
+    .bind(case_id)
+    .bind(&filter.document_type)
+    .bind(&filter.created_from)
+    .bind(&filter.created_to)
+    .fetch_all(pool)
+    .await
+    .map_err(DbError::query("Failed to list files"))
+}
+
+/// List a case's files in the order they appear in its bundle entries.
+///
+/// Files referenced by more than one entry sort by their first appearance;
+/// files not placed in the bundle follow, ordered by name.
+pub async fn list_files_in_bundle_order(
+    pool: &Pool<Sqlite>,
+    case_id: &str,
+) -> Result<Vec<File>, DbError> {
     sqlx::query_as::<_, File>(
-        "SELECT id, case_id, path, original_name, page_count, metadata_json, created_at
+        "SELECT f.id, f.case_id, f.path, f.original_name, f.page_count, f.metadata_json, f.content_hash, f.visual_hash, f.created_at
+         FROM files f
+         LEFT JOIN (
+             SELECT file_id, MIN(sequence_order) AS first_order
+             FROM artifact_entries
+             WHERE case_id = ? AND row_type = 'file'
+             GROUP BY file_id
+         ) e ON e.file_id = f.id
+         WHERE f.case_id = ?
+         ORDER BY e.first_order IS NULL, e.first_order ASC, f.original_name ASC",
+    )
+    .bind(case_id)
+    .bind(case_id)
+    .fetch_all(pool)
+    .await
+    .map_err(DbError::query("Failed to list files in bundle order"))
+}
+
+/// Read one top-level `metadata_json` field (e.g. `date`, `document_type`)
+/// for every file in a case. Files without the field yield `None`.
+pub async fn collect_metadata_field(
+    pool: &Pool<Sqlite>,
+    case_id: &str,
+    field: &str,
+) -> Result<Vec<MetadataFieldValue>, DbError> {
+    // The field becomes part of a JSON path, so only allow plain identifiers
+    if field.is_empty() || !field.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(DbError::Invalid(format!(
+            "Invalid metadata field: {}. Use letters, digits and underscores only",
+            field
+        )));
+    }
+
+    sqlx::query_as::<_, MetadataFieldValue>(
+        "SELECT id AS file_id,
+                CASE WHEN json_valid(metadata_json)
+                     THEN CAST(json_extract(metadata_json, ?) AS TEXT)
+                END AS value
          FROM files WHERE case_id = ? ORDER BY created_at DESC",
     )
+    .bind(format!("$.{}", field))
+    .bind(case_id)
+    .fetch_all(pool)
+    .await
+    .map_err(DbError::query("Failed to collect metadata field"))
+}
+
+/// Distinct detected `document_type` values among a case's files, with counts
+pub async fn list_document_types(
+    pool: &Pool<Sqlite>,
+    case_id: &str,
+) -> Result<Vec<DocumentTypeCount>, DbError> {
+    sqlx::query_as::<_, DocumentTypeCount>(
+        "SELECT document_type, COUNT(*) AS count
+         FROM (
+             SELECT CAST(json_extract(metadata_json, '$.document_type') AS TEXT) AS document_type
+             FROM files
+             WHERE case_id = ? AND json_valid(metadata_json)
+         )
+         WHERE document_type IS NOT NULL AND document_type != ''
+         GROUP BY document_type
+         ORDER BY count DESC, document_type ASC",
+    )
+    .bind(case_id)
+    .fetch_all(pool)
+    .await
+    .map_err(DbError::query("Failed to list document types"))
+}
+
+/// Document, file and page totals for a case. Files have no stored size, so
+/// the byte total is read from disk.
+pub async fn case_stats(pool: &Pool<Sqlite>, case_id: &str) -> Result<CaseStats, DbError> {
+    let (file_count, total_pages): (i64, i64) = sqlx::query_as(
+        "SELECT
+             (SELECT COUNT(*) FROM files WHERE case_id = c.id),
+             (SELECT COALESCE(SUM(page_count), 0) FROM files WHERE case_id = c.id)
+         FROM cases c
+         WHERE c.id = ? AND c.deleted_at IS NULL",
+    )
+    .bind(case_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(DbError::query("Failed to count case files"))?
+    .ok_or_else(|| DbError::not_found("Case", case_id))?;
+
+    let document_count = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM artifact_entries WHERE case_id = ? AND row_type = 'file'",
+    )
+    .bind(case_id)
+    .fetch_one(pool)
+    .await
+    .map_err(DbError::query("Failed to count case documents"))?;
+
+    let paths: Vec<String> = sqlx::query_scalar("SELECT path FROM files WHERE case_id = ?")
+        .bind(case_id)
+        .fetch_all(pool)
+        .await
+        .map_err(DbError::query("Failed to list case files"))?;
+    let total_file_bytes = paths
+        .iter()
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .map(|metadata| metadata.len())
+        .sum();
+
+    Ok(CaseStats {
+        document_count,
+        file_count,
+        total_pages,
+        total_file_bytes,
+    })
+}
+
+/// Ids of files placed in a case's entries that have no page count yet.
+/// Page ranges can't be computed until these are refreshed.
+pub async fn ensure_page_counts(
+    pool: &Pool<Sqlite>,
+    case_id: &str,
+) -> Result<Vec<String>, DbError> {
+    sqlx::query_scalar(
+        "SELECT f.id
+         FROM files f
+         JOIN artifact_entries e ON e.file_id = f.id
+         WHERE e.case_id = ? AND e.row_type = 'file' AND f.page_count IS NULL
+         GROUP BY f.id
+         ORDER BY MIN(e.sequence_order) ASC",
+    )
     .bind(case_id)
     .fetch_all(pool)
     .await
-    .map_err(|e| format!("Failed to list files: {}", e))
+    .map_err(DbError::query("Failed to check page counts"))
 }
 
 pub async fn create_file(
@@ -85,24 +411,31 @@ pub async fn create_file(
     original_name: &str,
     page_count: Option<i32>,
     metadata_json: Option<&str>,
-) -> Result<File, String> {
+    hashes: &FileHashes,
+) -> Result<File, DbError> {
     let id = uuid::Uuid::new_v4().to_string();
     let now = chrono::Utc::now().to_rfc3339();
 
-    sqlx::query(
-        "INSERT INTO files (id, case_id, path, original_name, page_count, metadata_json, created_at)
-         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    // Selecting the case keeps files from being added to a trashed case
+    let result = sqlx::query(
+        "INSERT INTO files (id, case_id, path, original_name, page_count, metadata_json, content_hash, visual_hash, created_at)
+         SELECT ?, id, ?, ?, ?, ?, ?, ?, ? FROM cases WHERE id = ? AND deleted_at IS NULL",
     )
     .bind(&id)
-    .bind(case_id)
     .bind(path)
     .bind(original_name)
     .bind(page_count)
     .bind(metadata_json)
+    .bind(&hashes.content_hash)
+    .bind(&hashes.visual_hash)
     .bind(&now)
+    .bind(case_id)
     .execute(pool)
     .await
-    .map_err(|e| format!("Failed to create file: {}", e))?;
+    .map_err(DbError::query("Failed to create file"))?;
+    if result.rows_affected() == 0 {
+        return Err(DbError::not_found("Case", case_id));
+    }
 
     Ok(File {
         id,
@@ -111,19 +444,105 @@ pub async fn create_file(
         original_name: original_name.to_string(),
         page_count,
         metadata_json: metadata_json.map(|s| s.to_string()),
+        content_hash: hashes.content_hash.clone(),
+        visual_hash: hashes.visual_hash.clone(),
         created_at: now,
     })
 }
 
-pub async fn get_file(pool: &Pool<Sqlite>, id: &str) -> Result<File, String> {
+/// The earliest imported file in a case with the given content hash
+pub async fn find_file_by_hash(
+    pool: &Pool<Sqlite>,
+    case_id: &str,
+    hash: &str,
+) -> Result<Option<File>, DbError> {
+    sqlx::query_as::<_, File>(
+        "SELECT id, case_id, path, original_name, page_count, metadata_json, content_hash, visual_hash, created_at
+         FROM files WHERE case_id = ? AND content_hash = ?
+         ORDER BY created_at ASC, rowid ASC LIMIT 1",
+    )
+    .bind(case_id)
+    .bind(hash.to_ascii_lowercase())
+    .fetch_optional(pool)
+    .await
+    .map_err(DbError::query("Failed to find file by hash"))
+}
+
+/// Group a case's files whose first-page scans look alike.
+///
+/// Files are linked when their perceptual hashes differ in at most
+/// `max_distance` bits, and linked files are grouped transitively. Only groups
+/// of two or more are returned, each in import order.
+pub async fn find_visual_duplicates(
+    pool: &Pool<Sqlite>,
+    case_id: &str,
+    max_distance: u32,
+) -> Result<Vec<Vec<File>>, DbError> {
+    let files = sqlx::query_as::<_, File>(
+        "SELECT id, case_id, path, original_name, page_count, metadata_json, content_hash, visual_hash, created_at
+         FROM files WHERE case_id = ? AND visual_hash IS NOT NULL
+         ORDER BY created_at ASC, rowid ASC",
+    )
+    .bind(case_id)
+    .fetch_all(pool)
+    .await
+    .map_err(DbError::query("Failed to load visual hashes"))?;
+
+    let hashes: Vec<Option<u64>> = files
+        .iter()
+        .map(|f| {
+            f.visual_hash
+                .as_deref()
+                .and_then(|h| u64::from_str_radix(h, 16).ok())
+        })
+        .collect();
+
+    // Union-find over files within max_distance of each other
+    let mut parent: Vec<usize> = (0..files.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for i in 0..files.len() {
+        for j in (i + 1)..files.len() {
+            if let (Some(a), Some(b)) = (hashes[i], hashes[j]) {
+                if crate::pdf::hamming_distance(a, b) <= max_distance {
+                    let (ri, rj) = (root(&mut parent, i), root(&mut parent, j));
+                    parent[ri.max(rj)] = ri.min(rj);
+                }
+            }
+        }
+    }
+
+    let mut groups: Vec<(usize, Vec<File>)> = Vec::new();
+    for (i, file) in files.into_iter().enumerate() {
+        let r = root(&mut parent, i);
+        match groups.iter_mut().find(|(group_root, _)| *group_root == r) {
+            Some((_, group)) => group.push(file),
+            None => groups.push((r, vec![file])),
+        }
+    }
+
+    Ok(groups
+        .into_iter()
+        .map(|(_, group)| group)
+        .filter(|group| group.len() > 1)
+        .collect())
+}
+
+pub async fn get_file(pool: &Pool<Sqlite>, id: &str) -> Result<File, DbError> {
     sqlx::query_as::<_, File>(
-        "SELECT id, case_id, path, original_name, page_count, metadata_json, created_at
-         FROM files WHERE id = ?",
+        "SELECT id, case_id, path, original_name, page_count, metadata_json, content_hash, visual_hash, created_at
+         FROM files WHERE id = ? AND case_id IN (SELECT id FROM cases WHERE deleted_at IS NULL)",
     )
     .bind(id)
-    .fetch_one(pool)
+    .fetch_optional(pool)
     .await
-    .map_err(|e| format!("File not found: {}", e))
+    .map_err(DbError::query("Failed to load file"))?
+    .ok_or_else(|| DbError::not_found("File", id))
 }
 
 pub async fn update_file(
@@ -131,27 +550,303 @@ pub async fn update_file(
     id: &str,
     page_count: Option<i32>,
     metadata_json: Option<&str>,
-) -> Result<File, String> {
-    sqlx::query("UPDATE files SET page_count = ?, metadata_json = ? WHERE id = ?")
-        .bind(page_count)
-        .bind(metadata_json)
-        .bind(id)
-        .execute(pool)
-        .await
-        .map_err(|e| format!("Failed to update file: {}", e))?;
+) -> Result<File, DbError> {
+    sqlx::query(
+        "UPDATE files SET page_count = ?, metadata_json = ?
+         WHERE id = ? AND case_id IN (SELECT id FROM cases WHERE deleted_at IS NULL)",
+    )
+    .bind(page_count)
+    .bind(metadata_json)
+    .bind(id)
+    .execute(pool)
+    .await
+    .map_err(DbError::query("Failed to update file"))?;
 
     get_file(pool, id).await
 }
 
-pub async fn delete_file(pool: &Pool<Sqlite>, id: &str) -> Result<(), String> {
-    sqlx::query("DELETE FROM files WHERE id = ?")
+/// Point a file record at a new location on disk after the original moved.
+///
+/// The new path must be a readable, unencrypted PDF. Its page count is refreshed
+/// and `hashes`, computed from the new path by the caller, are stored; if the
+/// content differs from the stored hash the relink still goes ahead but a
+/// warning is returned.
+pub async fn relink_file(
+    pool: &Pool<Sqlite>,
+    file_id: &str,
+    new_path: &str,
+    validation: &PdfValidation,
+    hashes: &FileHashes,
+) -> Result<RelinkResult, DbError> {
+    let existing = get_file(pool, file_id).await?;
+
+    if !std::path::Path::new(new_path).is_file() {
+        return Err(DbError::Invalid(format!("File not found: {}", new_path)));
+    }
+    if let Some(error) = &validation.error {
+        return Err(DbError::Invalid(format!(
+            "Cannot relink to {}: {}",
+            new_path, error
+        )));
+    }
+
+    let warning = match (&existing.content_hash, &hashes.content_hash) {
+        (Some(stored), Some(current)) if stored != current => Some(format!(
+            "{} has different content from the file originally imported as {}",
+            new_path, existing.original_name
+        )),
+        _ => None,
+    };
+
+    sqlx::query(
+        "UPDATE files SET path = ?, page_count = ?, content_hash = ?, visual_hash = ? WHERE id = ?",
+    )
+    .bind(new_path)
+    .bind(validation.page_count as i32)
+    .bind(&hashes.content_hash)
+    .bind(&hashes.visual_hash)
+    .bind(file_id)
+    .execute(pool)
+    .await
+    .map_err(DbError::query("Failed to relink file"))?;
+
+    Ok(RelinkResult {
+        file: get_file(pool, file_id).await?,
+        warning,
+    })
+}
+
+pub async fn delete_file(pool: &Pool<Sqlite>, id: &str) -> Result<(), DbError> {
+    sqlx::query(
+        "DELETE FROM files
+         WHERE id = ? AND case_id IN (SELECT id FROM cases WHERE deleted_at IS NULL)",
+    )
+    .bind(id)
+    .execute(pool)
+    .await
+    .map_err(DbError::query("Failed to delete file"))?;
+    Ok(())
+}
+
+/// Delete several files in one transaction and return how many were removed.
+/// Repeated ids are deleted once. If any id doesn't exist nothing is deleted.
+pub async fn delete_files(pool: &Pool<Sqlite>, ids: &[String]) -> Result<u64, DbError> {
+    let ids: BTreeSet<&String> = ids.iter().collect();
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(DbError::query("Failed to delete files"))?;
+    let mut removed = 0;
+    for id in ids {
+        let result = sqlx::query(
+            "DELETE FROM files
+             WHERE id = ? AND case_id IN (SELECT id FROM cases WHERE deleted_at IS NULL)",
+        )
         .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(DbError::query("Failed to delete file"))?;
+        if result.rows_affected() == 0 {
+            // Dropping the transaction rolls back the deletes so far
+            return Err(DbError::not_found("File", id));
+        }
+        removed += result.rows_affected();
+    }
+    tx.commit()
+        .await
+        .map_err(DbError::query("Failed to delete files"))?;
+    Ok(removed)
+}
+
+// ============================================================================
+// FILE TAGS
+// ============================================================================
+
+/// Tags are compared case-insensitively and stored trimmed and lowercased
+fn normalize_tag(tag: &str) -> Result<String, DbError> {
+    let tag = tag.trim().to_lowercase();
+    if tag.is_empty() {
+        return Err(DbError::Invalid("Tag cannot be empty".to_string()));
+    }
+    Ok(tag)
+}
+
+/// Tag a file; adding a tag it already has is a no-op
+pub async fn add_file_tag(pool: &Pool<Sqlite>, file_id: &str, tag: &str) -> Result<(), DbError> {
+    let tag = normalize_tag(tag)?;
+    get_file(pool, file_id).await?;
+
+    sqlx::query("INSERT OR IGNORE INTO file_tags (file_id, tag, created_at) VALUES (?, ?, ?)")
+        .bind(file_id)
+        .bind(&tag)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(pool)
+        .await
+        .map_err(DbError::query("Failed to add file tag"))?;
+    Ok(())
+}
+
+pub async fn remove_file_tag(pool: &Pool<Sqlite>, file_id: &str, tag: &str) -> Result<(), DbError> {
+    let tag = normalize_tag(tag)?;
+    get_file(pool, file_id).await?;
+
+    sqlx::query("DELETE FROM file_tags WHERE file_id = ? AND tag = ?")
+        .bind(file_id)
+        .bind(&tag)
         .execute(pool)
         .await
-        .map_err(|e| format!("Failed to delete file: {}", e))?;
+        .map_err(DbError::query("Failed to remove file tag"))?;
+    Ok(())
+}
+
+pub async fn list_file_tags(pool: &Pool<Sqlite>, file_id: &str) -> Result<Vec<String>, DbError> {
+    get_file(pool, file_id).await?;
+    sqlx::query_scalar("SELECT tag FROM file_tags WHERE file_id = ? ORDER BY tag ASC")
+        .bind(file_id)
+        .fetch_all(pool)
+        .await
+        .map_err(DbError::query("Failed to list file tags"))
+}
+
+/// A case's files carrying `tag`, newest first
+pub async fn list_files_by_tag(
+    pool: &Pool<Sqlite>,
+    case_id: &str,
+    tag: &str,
+) -> Result<Vec<File>, DbError> {
+    sqlx::query_as::<_, File>(
+        "SELECT f.id, f.case_id, f.path, f.original_name, f.page_count, f.metadata_json, f.content_hash, f.visual_hash, f.created_at
+         FROM files f
+         JOIN file_tags t ON t.file_id = f.id
+         JOIN cases c ON c.id = f.case_id
+         WHERE f.case_id = ? AND t.tag = ? AND c.deleted_at IS NULL
+         ORDER BY f.created_at DESC",
+    )
+    .bind(case_id)
+    .bind(normalize_tag(tag)?)
+    .fetch_all(pool)
+    .await
+    .map_err(DbError::query("Failed to list files by tag"))
+}
+
+// ============================================================================
+// SETTINGS
+// ============================================================================
+
+const DESCRIPTION_LIMIT_KEY: &str = "description_limit";
+
+pub async fn get_description_limit(
+    pool: &Pool<Sqlite>,
+) -> Result<Option<DescriptionLimit>, DbError> {
+    let value: Option<String> = sqlx::query_scalar("SELECT value FROM app_settings WHERE key = ?")
+        .bind(DESCRIPTION_LIMIT_KEY)
+        .fetch_optional(pool)
+        .await
+        .map_err(DbError::query("Failed to read description limit"))?;
+
+    value
+        .map(|v| {
+            serde_json::from_str(&v)
+                .map_err(|e| DbError::Invalid(format!("Invalid description limit: {}", e)))
+        })
+        .transpose()
+}
+
+/// Set (or clear, with `None`) the maximum entry description length
+pub async fn set_description_limit(
+    pool: &Pool<Sqlite>,
+    limit: Option<&DescriptionLimit>,
+) -> Result<(), DbError> {
+    match limit {
+        Some(limit) => {
+            if limit.max_length == 0 {
+                return Err(DbError::Invalid(
+                    "Description limit must be at least 1 character".to_string(),
+                ));
+            }
+            let value = serde_json::to_string(limit).map_err(|e| {
+                DbError::Invalid(format!("Failed to encode description limit: {}", e))
+            })?;
+            sqlx::query(
+                "INSERT INTO app_settings (key, value) VALUES (?, ?)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            )
+            .bind(DESCRIPTION_LIMIT_KEY)
+            .bind(value)
+            .execute(pool)
+            .await
+            .map_err(DbError::query("Failed to save description limit"))?;
+        }
+        None => {
+            sqlx::query("DELETE FROM app_settings WHERE key = ?")
+                .bind(DESCRIPTION_LIMIT_KEY)
+                .execute(pool)
+                .await
+                .map_err(DbError::query("Failed to clear description limit"))?;
+        }
+    }
     Ok(())
 }
 
+/// Shorten `text` to at most `max_chars` characters without splitting a word
+fn truncate_at_word_boundary(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+
+    let cut: String = text.chars().take(max_chars).collect();
+    let ends_on_boundary = text.chars().nth(max_chars).is_none_or(char::is_whitespace);
+
+    let kept = if ends_on_boundary {
+        cut.as_str()
+    } else {
+        // A single over-long word has no boundary to back up to
+        match cut.rfind(char::is_whitespace) {
+            Some(idx) => &cut[..idx],
+            None => cut.as_str(),
+        }
+    };
+    kept.trim_end().to_string()
+}
+
+/// Apply the configured description limit to an entry's `config_json`.
+///
+/// Returns the config to store: unchanged, with a truncated `description`,
+/// or an error when the limit is in reject mode.
+async fn enforce_description_limit(
+    pool: &Pool<Sqlite>,
+    config_json: Option<&str>,
+) -> Result<Option<String>, DbError> {
+    let Some(config_json) = config_json else {
+        return Ok(None);
+    };
+    let Some(limit) = get_description_limit(pool).await? else {
+        return Ok(Some(config_json.to_string()));
+    };
+    let Ok(mut config) = serde_json::from_str::<serde_json::Value>(config_json) else {
+        return Ok(Some(config_json.to_string()));
+    };
+    let Some(description) = config.get("description").and_then(|d| d.as_str()) else {
+        return Ok(Some(config_json.to_string()));
+    };
+
+    if description.chars().count() <= limit.max_length {
+        return Ok(Some(config_json.to_string()));
+    }
+
+    match limit.mode {
+        DescriptionLimitMode::Reject => Err(DbError::Invalid(format!(
+            "Description exceeds the {} character limit",
+            limit.max_length
+        ))),
+        DescriptionLimitMode::Truncate => {
+            let truncated = truncate_at_word_boundary(description, limit.max_length);
+            config["description"] = serde_json::Value::String(truncated);
+            Ok(Some(config.to_string()))
+        }
+    }
+}
+
 // ============================================================================
 // ENTRY CRUD
 // ============================================================================
@@ -159,7 +854,7 @@ pub async fn delete_file(pool: &Pool<Sqlite>, id: &str) -> Result<(), String> {
 pub async fn list_entries(
     pool: &Pool<Sqlite>,
     case_id: &str,
-) -> Result<Vec<ArtifactEntry>, String> {
+) -> Result<Vec<ArtifactEntry>, DbError> {
     sqlx::query_as::<_, ArtifactEntry>(
         "SELECT id, case_id, sequence_order, row_type, file_id, config_json, label_override, created_at
          FROM artifact_entries WHERE case_id = ? ORDER BY sequence_order ASC",
@@ -167,7 +862,7 @@ pub async fn list_entries(
     .bind(case_id)
     .fetch_all(pool)
     .await
-    .map_err(|e| format!("Failed to list entries: {}", e))
+    .map_err(DbError::query("Failed to list entries"))
 }
 
 pub async fn create_entry(
@@ -178,42 +873,50 @@ pub async fn create_entry(
     file_id: Option<&str>,
     config_json: Option<&str>,
     label_override: Option<&str>,
-) -> Result<ArtifactEntry, String> {
+) -> Result<ArtifactEntry, DbError> {
     if !["file", "component"].contains(&row_type) {
-        return Err(format!(
+        return Err(DbError::Invalid(format!(
             "Invalid row_type: {}. Must be 'file' or 'component'",
             row_type
-        ));
+        )));
     }
 
     match row_type {
         "file" if file_id.is_none() => {
-            return Err("file_id is required when row_type is 'file'".to_string())
+            return Err(DbError::Invalid(
+                "file_id is required when row_type is 'file'".to_string(),
+            ))
         }
         "component" if config_json.is_none() => {
-            return Err("config_json is required when row_type is 'component'".to_string())
+            return Err(DbError::Invalid(
+                "config_json is required when row_type is 'component'".to_string(),
+            ))
         }
         _ => {}
     }
 
+    let config_json = enforce_description_limit(pool, config_json).await?;
     let id = uuid::Uuid::new_v4().to_string();
     let now = chrono::Utc::now().to_rfc3339();
 
-    sqlx::query(
+    let result = sqlx::query(
         "INSERT INTO artifact_entries (id, case_id, sequence_order, row_type, file_id, config_json, label_override, created_at)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+         SELECT ?, id, ?, ?, ?, ?, ?, ? FROM cases WHERE id = ? AND deleted_at IS NULL",
     )
     .bind(&id)
-    .bind(case_id)
     .bind(sequence_order)
     .bind(row_type)
     .bind(file_id)
-    .bind(config_json)
+    .bind(&config_json)
     .bind(label_override)
     .bind(&now)
+    .bind(case_id)
     .execute(pool)
     .await
-    .map_err(|e| format!("Failed to create entry: {}", e))?;
+    .map_err(DbError::query("Failed to create entry"))?;
+    if result.rows_affected() == 0 {
+        return Err(DbError::not_found("Case", case_id));
+    }
 
     Ok(ArtifactEntry {
         id,
@@ -221,7 +924,7 @@ pub async fn create_entry(
         sequence_order,
         row_type: row_type.to_string(),
         file_id: file_id.map(|s| s.to_string()),
-        config_json: config_json.map(|s| s.to_string()),
+        config_json,
         label_override: label_override.map(|s| s.to_string()),
         created_at: now,
     })
@@ -233,7 +936,9 @@ pub async fn update_entry(
     sequence_order: Option<i32>,
     config_json: Option<&str>,
     label_override: Option<&str>,
-) -> Result<ArtifactEntry, String> {
+) -> Result<ArtifactEntry, DbError> {
+    let config_json = enforce_description_limit(pool, config_json).await?;
+
     sqlx::query(
         "UPDATE artifact_entries SET
             sequence_order = COALESCE(?, sequence_order),
@@ -242,29 +947,30 @@ pub async fn update_entry(
          WHERE id = ?",
     )
     .bind(sequence_order)
-    .bind(config_json)
+    .bind(&config_json)
     .bind(label_override)
     .bind(id)
     .execute(pool)
     .await
-    .map_err(|e| format!("Failed to update entry: {}", e))?;
+    .map_err(DbError::query("Failed to update entry"))?;
 
     sqlx::query_as::<_, ArtifactEntry>(
         "SELECT id, case_id, sequence_order, row_type, file_id, config_json, label_override, created_at
          FROM artifact_entries WHERE id = ?",
     )
     .bind(id)
-    .fetch_one(pool)
+    .fetch_optional(pool)
     .await
-    .map_err(|e| format!("Entry not found: {}", e))
+    .map_err(DbError::query("Failed to load entry"))?
+    .ok_or_else(|| DbError::not_found("Entry", id))
 }
 
-pub async fn delete_entry(pool: &Pool<Sqlite>, id: &str) -> Result<(), String> {
+pub async fn delete_entry(pool: &Pool<Sqlite>, id: &str) -> Result<(), DbError> {
     sqlx::query("DELETE FROM artifact_entries WHERE id = ?")
         .bind(id)
         .execute(pool)
         .await
-        .map_err(|e| format!("Failed to delete entry: {}", e))?;
+        .map_err(DbError::query("Failed to delete entry"))?;
     Ok(())
 }
 
@@ -272,7 +978,7 @@ pub async fn reorder_entries(
     pool: &Pool<Sqlite>,
     case_id: &str,
     entry_ids: Vec<String>,
-) -> Result<Vec<ArtifactEntry>, String> {
+) -> Result<Vec<ArtifactEntry>, DbError> {
     for (index, entry_id) in entry_ids.iter().enumerate() {
         sqlx::query("UPDATE artifact_entries SET sequence_order = ? WHERE id = ? AND case_id = ?")
             .bind(index as i32)
@@ -280,12 +986,161 @@ pub async fn reorder_entries(
             .bind(case_id)
             .execute(pool)
             .await
-            .map_err(|e| format!("Failed to reorder entry {}: {}", entry_id, e))?;
+            .map_err(DbError::query(format!(
+                "Failed to reorder entry {}",
+                entry_id
+            )))?;
     }
 
     list_entries(pool, case_id).await
 }
 
+// ============================================================================
+// NAME INDEX
+// ============================================================================
+
+/// The files placed in a case's bundle, in sequence order, for the name index.
+/// Component entries have no pages here.
+pub async fn name_index_sources(
+    pool: &Pool<Sqlite>,
+    case_id: &str,
+) -> Result<Vec<NameIndexSource>, DbError> {
+    let rows: Vec<(String, Option<i32>)> = sqlx::query_as(
+        "SELECT f.path, f.page_count
+         FROM artifact_entries e
+         JOIN files f ON f.id = e.file_id
+         WHERE e.case_id = ? AND e.row_type = 'file'
+         ORDER BY e.sequence_order ASC",
+    )
+    .bind(case_id)
+    .fetch_all(pool)
+    .await
+    .map_err(DbError::query("Failed to list bundle files"))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(path, page_count)| NameIndexSource {
+            path,
+            page_count: page_count.map(|count| count.max(0) as usize),
+        })
+        .collect())
+}
+
+// ============================================================================
+// BUNDLE DRAFTS
+// ============================================================================
+
+/// Store the bundle builder's in-progress state for a case, replacing any previous draft
+pub async fn save_bundle_draft(
+    pool: &Pool<Sqlite>,
+    case_id: &str,
+    draft_json: &str,
+) -> Result<(), DbError> {
+    serde_json::from_str::<serde_json::Value>(draft_json)
+        .map_err(|e| DbError::Invalid(format!("Invalid draft JSON: {}", e)))?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        "INSERT INTO bundle_drafts (case_id, draft_json, updated_at) VALUES (?, ?, ?)
+         ON CONFLICT(case_id) DO UPDATE SET draft_json = excluded.draft_json, updated_at = excluded.updated_at",
+    )
+    .bind(case_id)
+    .bind(draft_json)
+    .bind(&now)
+    .execute(pool)
+    .await
+    .map_err(DbError::query("Failed to save bundle draft"))?;
+    Ok(())
+}
+
+pub async fn load_bundle_draft(
+    pool: &Pool<Sqlite>,
+    case_id: &str,
+) -> Result<Option<String>, DbError> {
+    sqlx::query_scalar("SELECT draft_json FROM bundle_drafts WHERE case_id = ?")
+        .bind(case_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(DbError::query("Failed to load bundle draft"))
+}
+
+// ============================================================================
+// COMPILE HISTORY
+// ============================================================================
+
+/// Compiles considered when fitting the estimate
+const COMPILE_HISTORY_WINDOW: i64 = 20;
+
+/// Fewest compiles needed before the fitted model replaces the default rate
+const MIN_COMPILE_HISTORY: usize = 3;
+
+/// Rate assumed until there is enough history
+const DEFAULT_COMPILE_MS_PER_PAGE: f64 = 50.0;
+
+/// Record how long a successful compile took
+pub async fn record_compile(
+    pool: &Pool<Sqlite>,
+    case_id: &str,
+    page_count: u32,
+    duration_ms: u64,
+) -> Result<(), DbError> {
+    sqlx::query(
+        "INSERT INTO compile_history (id, case_id, page_count, duration_ms, compiled_at)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(case_id)
+    .bind(page_count)
+    .bind(duration_ms as i64)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(pool)
+    .await
+    .map_err(DbError::query("Failed to record compile"))?;
+    Ok(())
+}
+
+/// Estimate how long compiling `total_pages` pages will take.
+///
+/// Fits a least-squares line (pages -> milliseconds) to the most recent
+/// compiles across all cases. Falls back to a fixed per-page rate when there
+/// are too few compiles or they don't produce a sensible fit.
+pub async fn estimate_compile_time(
+    pool: &Pool<Sqlite>,
+    total_pages: u32,
+) -> Result<Duration, DbError> {
+    let history: Vec<(i64, i64)> = sqlx::query_as(
+        "SELECT page_count, duration_ms FROM compile_history
+         ORDER BY compiled_at DESC, rowid DESC LIMIT ?",
+    )
+    .bind(COMPILE_HISTORY_WINDOW)
+    .fetch_all(pool)
+    .await
+    .map_err(DbError::query("Failed to load compile history"))?;
+
+    let pages = f64::from(total_pages);
+    let fallback = Duration::from_millis((pages * DEFAULT_COMPILE_MS_PER_PAGE) as u64);
+    if history.len() < MIN_COMPILE_HISTORY {
+        return Ok(fallback);
+    }
+
+    let n = history.len() as f64;
+    let mean_x = history.iter().map(|&(x, _)| x as f64).sum::<f64>() / n;
+    let mean_y = history.iter().map(|&(_, y)| y as f64).sum::<f64>() / n;
+    let (covariance, variance) = history.iter().fold((0.0, 0.0), |(cov, var), &(x, y)| {
+        let dx = x as f64 - mean_x;
+        (cov + dx * (y as f64 - mean_y), var + dx * dx)
+    });
+    if variance == 0.0 || covariance <= 0.0 {
+        return Ok(fallback);
+    }
+
+    let slope = covariance / variance;
+    let intercept = mean_y - slope * mean_x;
+    Ok(Duration::from_millis(
+        (intercept + slope * pages).max(0.0) as u64
+    ))
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -320,14 +1175,43 @@ mod tests {
         assert_eq!(case.case_type, "bundle");
         assert!(!case.id.is_empty());
 
-        let cases = list_cases(&pool).await.unwrap();
+        let cases = list_cases(&pool, None, None).await.unwrap().cases;
         assert_eq!(cases.len(), 1);
 
         delete_case(&pool, &case.id).await.unwrap();
-        let cases = list_cases(&pool).await.unwrap();
+        let cases = list_cases(&pool, None, None).await.unwrap().cases;
         assert!(cases.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_list_cases_pages() {
+        let pool = setup_test_db().await;
+        for (i, name) in ["A", "B", "C", "D", "E"].into_iter().enumerate() {
+            let case = create_case(&pool, name, "bundle", None).await.unwrap();
+            sqlx::query("UPDATE cases SET updated_at = ? WHERE id = ?")
+                .bind(format!("2026-01-0{}T00:00:00+00:00", i + 1))
+                .bind(&case.id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        let names = |page: CasePage| -> Vec<String> {
+            page.cases.into_iter().map(|case| case.name).collect()
+        };
+
+        let page = list_cases(&pool, Some(2), Some(1)).await.unwrap();
+        assert_eq!(page.total, 5);
+        assert_eq!(names(page), vec!["D", "C"]);
+
+        let last = list_cases(&pool, Some(2), Some(4)).await.unwrap();
+        assert_eq!(last.total, 5);
+        assert_eq!(names(last), vec!["A"]);
+
+        let rest = list_cases(&pool, None, Some(3)).await.unwrap();
+        assert_eq!(names(rest), vec!["B", "A"]);
+        assert!(list_cases(&pool, Some(-1), None).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_file_crud() {
         let pool = setup_test_db().await;
@@ -342,6 +1226,7 @@ mod tests {
             "invoice.pdf",
             Some(5),
             Some(r#"{"date": "2024-01-15"}"#),
+            &FileHashes::default(),
         )
         .await
         .unwrap();
@@ -363,19 +1248,1260 @@ mod tests {
         assert!(files.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_delete_files_in_one_transaction() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Test Case", "bundle", None)
+            .await
+            .unwrap();
+        let mut ids = Vec::new();
+        for name in ["a.pdf", "b.pdf", "c.pdf", "d.pdf", "e.pdf"] {
+            let file = create_file(
+                &pool,
+                &case.id,
+                "/p",
+                name,
+                None,
+                None,
+                &FileHashes::default(),
+            )
+            .await
+            .unwrap();
+            ids.push(file.id);
+        }
+
+        // One unknown id rolls back the whole batch
+        let with_missing = vec![ids[0].clone(), "missing-id".to_string()];
+        assert!(matches!(
+            delete_files(&pool, &with_missing).await.unwrap_err(),
+            DbError::NotFound { entity: "File", .. }
+        ));
+        assert_eq!(list_files(&pool, &case.id).await.unwrap().len(), 5);
+
+        // A selection sent twice still deletes each file once
+        let with_repeat = vec![
+            ids[0].clone(),
+            ids[1].clone(),
+            ids[0].clone(),
+            ids[2].clone(),
+        ];
+        assert_eq!(delete_files(&pool, &with_repeat).await.unwrap(), 3);
+        let mut remaining: Vec<String> = list_files(&pool, &case.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|f| f.original_name)
+            .collect();
+        remaining.sort();
+        assert_eq!(remaining, vec!["d.pdf", "e.pdf"]);
+    }
+
     #[tokio::test]
     async fn test_file_cascade_delete() {
         let pool = setup_test_db().await;
         let case = create_case(&pool, "Test Case", "bundle", None)
             .await
             .unwrap();
-        create_file(&pool, &case.id, "/path/file.pdf", "file.pdf", None, None)
+        create_file(
+            &pool,
+            &case.id,
+            "/path/file.pdf",
+            "file.pdf",
+            None,
+            None,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+
+        // Trashing keeps the rows; only purging removes them
+        let count_files = || async {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM files WHERE case_id = ?")
+                .bind(&case.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+        };
+        delete_case(&pool, &case.id).await.unwrap();
+        assert_eq!(count_files().await, 1);
+
+        purge_case(&pool, &case.id).await.unwrap();
+        assert_eq!(count_files().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_trash_and_restore_case() {
+        let pool = setup_test_db().await;
+        let kept = create_case(&pool, "Tan v Lim", "bundle", None)
             .await
             .unwrap();
+        let trashed = create_case(&pool, "Re Ong", "affidavit", None)
+            .await
+            .unwrap();
+
+        delete_case(&pool, &trashed.id).await.unwrap();
+        let live: Vec<String> = list_cases(&pool, None, None)
+            .await
+            .unwrap()
+            .cases
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        assert_eq!(live, vec![kept.id.clone()]);
+        let in_trash = list_trashed_cases(&pool).await.unwrap();
+        assert_eq!(in_trash.len(), 1);
+        assert_eq!(in_trash[0].id, trashed.id);
+
+        // Only trashed cases can be purged or restored
+        assert!(matches!(
+            purge_case(&pool, &kept.id).await.unwrap_err(),
+            DbError::NotFound {
+                entity: "Trashed case",
+                ..
+            }
+        ));
+        assert!(restore_case(&pool, &kept.id).await.is_err());
+
+        restore_case(&pool, &trashed.id).await.unwrap();
+        assert_eq!(list_cases(&pool, None, None).await.unwrap().cases.len(), 2);
+        assert!(list_trashed_cases(&pool).await.unwrap().is_empty());
+
+        delete_case(&pool, &trashed.id).await.unwrap();
+        purge_case(&pool, &trashed.id).await.unwrap();
+        assert!(list_trashed_cases(&pool).await.unwrap().is_empty());
+        assert!(restore_case(&pool, &trashed.id).await.is_err());
+        assert!(delete_case(&pool, "missing-id").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_files_in_bundle_order() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Test Case", "bundle", None)
+            .await
+            .unwrap();
+
+        let unplaced = create_file(
+            &pool,
+            &case.id,
+            "/path/a.pdf",
+            "a.pdf",
+            None,
+            None,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+        let second = create_file(
+            &pool,
+            &case.id,
+            "/path/b.pdf",
+            "b.pdf",
+            None,
+            None,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+        let first = create_file(
+            &pool,
+            &case.id,
+            "/path/c.pdf",
+            "c.pdf",
+            None,
+            None,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+
+        create_entry(&pool, &case.id, 0, "file", Some(&first.id), None, None)
+            .await
+            .unwrap();
+        create_entry(&pool, &case.id, 1, "component", None, Some("{}"), None)
+            .await
+            .unwrap();
+        create_entry(&pool, &case.id, 2, "file", Some(&second.id), None, None)
+            .await
+            .unwrap();
+
+        let files = list_files_in_bundle_order(&pool, &case.id).await.unwrap();
+        let ids: Vec<&str> = files.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![first.id.as_str(), second.id.as_str(), unplaced.id.as_str()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_collect_metadata_field() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Test Case", "bundle", None)
+            .await
+            .unwrap();
+
+        let invoice = create_file(
+            &pool,
+            &case.id,
+            "/path/invoice.pdf",
+            "invoice.pdf",
+            None,
+            Some(r#"{"document_type": "Invoice", "date": "2024-01-15"}"#),
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+        let letter = create_file(
+            &pool,
+            &case.id,
+            "/path/letter.pdf",
+            "letter.pdf",
+            None,
+            Some(r#"{"document_type": "Letter"}"#),
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+        let bare = create_file(
+            &pool,
+            &case.id,
+            "/path/scan.pdf",
+            "scan.pdf",
+            None,
+            None,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+
+        let values = collect_metadata_field(&pool, &case.id, "document_type")
+            .await
+            .unwrap();
+        assert_eq!(values.len(), 3);
+        let value_of = |id: &str| {
+            values
+                .iter()
+                .find(|v| v.file_id == id)
+                .and_then(|v| v.value.clone())
+        };
+        assert_eq!(value_of(&invoice.id).as_deref(), Some("Invoice"));
+        assert_eq!(value_of(&letter.id).as_deref(), Some("Letter"));
+        assert_eq!(value_of(&bare.id), None);
+
+        assert!(collect_metadata_field(&pool, &case.id, "date') OR 1=1 --")
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_truncate_at_word_boundary() {
+        assert_eq!(
+            truncate_at_word_boundary("Letter from Acme", 20),
+            "Letter from Acme"
+        );
+        // Cut lands mid-word: back up to the previous space
+        assert_eq!(
+            truncate_at_word_boundary("Letter from Acme Pte Ltd", 14),
+            "Letter from"
+        );
+        // Cut lands exactly on a word boundary
+        assert_eq!(
+            truncate_at_word_boundary("Letter from Acme", 11),
+            "Letter from"
+        );
+        // No boundary to back up to
+        assert_eq!(truncate_at_word_boundary("Correspondence", 6), "Corres");
+    }
+
+    #[tokio::test]
+    async fn test_description_limit_truncates() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Test Case", "bundle", None)
+            .await
+            .unwrap();
+        set_description_limit(
+            &pool,
+            Some(&DescriptionLimit {
+                max_length: 14,
+                mode: DescriptionLimitMode::Truncate,
+            }),
+        )
+        .await
+        .unwrap();
+
+        let entry = create_entry(
+            &pool,
+            &case.id,
+            0,
+            "component",
+            None,
+            Some(r#"{"description": "Letter from Acme Pte Ltd", "date": ""}"#),
+            None,
+        )
+        .await
+        .unwrap();
+        let config: serde_json::Value =
+            serde_json::from_str(entry.config_json.as_deref().unwrap()).unwrap();
+        assert_eq!(config["description"], "Letter from");
+        assert_eq!(config["date"], "");
+
+        let stored = list_entries(&pool, &case.id).await.unwrap();
+        assert_eq!(stored[0].config_json, entry.config_json);
+    }
+
+    #[tokio::test]
+    async fn test_description_limit_rejects() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Test Case", "bundle", None)
+            .await
+            .unwrap();
+        let entry = create_entry(
+            &pool,
+            &case.id,
+            0,
+            "component",
+            None,
+            Some(r#"{"description": "Cover Page"}"#),
+            None,
+        )
+        .await
+        .unwrap();
+
+        set_description_limit(
+            &pool,
+            Some(&DescriptionLimit {
+                max_length: 10,
+                mode: DescriptionLimitMode::Reject,
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            get_description_limit(&pool)
+                .await
+                .unwrap()
+                .map(|l| l.max_length),
+            Some(10)
+        );
+
+        let result = update_entry(
+            &pool,
+            &entry.id,
+            None,
+            Some(r#"{"description": "Cover Page for Bundle"}"#),
+            None,
+        )
+        .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("10 character limit"));
+
+        // Clearing the limit allows long descriptions again
+        set_description_limit(&pool, None).await.unwrap();
+        assert!(update_entry(
+            &pool,
+            &entry.id,
+            None,
+            Some(r#"{"description": "Cover Page for Bundle"}"#),
+            None,
+        )
+        .await
+        .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_bundle_draft_round_trip() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Draft Case", "bundle", None)
+            .await
+            .unwrap();
+
+        assert_eq!(load_bundle_draft(&pool, &case.id).await.unwrap(), None);
+
+        let draft = r#"{"selected":["e1","e2"],"style":"roman","options":{"toc":true}}"#;
+        save_bundle_draft(&pool, &case.id, draft).await.unwrap();
+        assert_eq!(
+            load_bundle_draft(&pool, &case.id).await.unwrap().as_deref(),
+            Some(draft)
+        );
+
+        let updated = r#"{"selected":["e2"],"style":"arabic"}"#;
+        save_bundle_draft(&pool, &case.id, updated).await.unwrap();
+        assert_eq!(
+            load_bundle_draft(&pool, &case.id).await.unwrap().as_deref(),
+            Some(updated)
+        );
+
+        assert!(save_bundle_draft(&pool, &case.id, "{not json")
+            .await
+            .is_err());
 
         delete_case(&pool, &case.id).await.unwrap();
-        let files = list_files(&pool, &case.id).await.unwrap();
-        assert!(files.is_empty());
+        purge_case(&pool, &case.id).await.unwrap();
+        assert_eq!(load_bundle_draft(&pool, &case.id).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_missing_rows_are_not_found() {
+        let pool = setup_test_db().await;
+
+        let err = get_file(&pool, "missing-id").await.unwrap_err();
+        assert!(matches!(
+            err,
+            DbError::NotFound { entity: "File", ref id } if id == "missing-id"
+        ));
+
+        let err = update_entry(&pool, "missing-entry", Some(1), None, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            DbError::NotFound {
+                entity: "Entry",
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_list_files_filtered() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Test Case", "bundle", None)
+            .await
+            .unwrap();
+        for (name, metadata, created_at) in [
+            (
+                "a.pdf",
+                Some(r#"{"document_type":"Letter"}"#),
+                "2026-01-05T09:00:00+00:00",
+            ),
+            (
+                "b.pdf",
+                Some(r#"{"document_type":"Invoice"}"#),
+                "2026-02-10T09:00:00+00:00",
+            ),
+            (
+                "c.pdf",
+                Some(r#"{"document_type":"Letter"}"#),
+                "2026-03-15T09:00:00+00:00",
+            ),
+            ("d.pdf", Some("not json"), "2026-03-20T09:00:00+00:00"),
+            ("e.pdf", None, "2026-03-25T09:00:00+00:00"),
+        ] {
+            let file = create_file(
+                &pool,
+                &case.id,
+                "/p",
+                name,
+                None,
+                metadata,
+                &FileHashes::default(),
+            )
+            .await
+            .unwrap();
+            sqlx::query("UPDATE files SET created_at = ? WHERE id = ?")
+                .bind(created_at)
+                .bind(&file.id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        let names = |files: Vec<File>| -> Vec<String> {
+            files.into_iter().map(|f| f.original_name).collect()
+        };
+
+        let all = list_files_filtered(&pool, &case.id, &FileFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 5);
+
+        let letters = FileFilter {
+            document_type: Some("letter".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(
+                list_files_filtered(&pool, &case.id, &letters)
+                    .await
+                    .unwrap()
+            ),
+            vec!["c.pdf", "a.pdf"]
+        );
+
+        let range = FileFilter {
+            created_from: Some("2026-02-10".to_string()),
+            created_to: Some("2026-03-20".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(list_files_filtered(&pool, &case.id, &range).await.unwrap()),
+            vec!["d.pdf", "c.pdf", "b.pdf"]
+        );
+
+        let both = FileFilter {
+            document_type: Some("Letter".to_string()),
+            created_from: Some("2026-02-01".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(list_files_filtered(&pool, &case.id, &both).await.unwrap()),
+            vec!["c.pdf"]
+        );
+
+        let bad_date = FileFilter {
+            created_to: Some("15/03/2026".to_string()),
+            ..Default::default()
+        };
+        assert!(list_files_filtered(&pool, &case.id, &bad_date)
+            .await
+            .is_err());
     }
-}
 
+    #[tokio::test]
+    async fn test_list_document_types() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Types Case", "bundle", None)
+            .await
+            .unwrap();
+
+        for (name, metadata) in [
+            ("a.pdf", Some(r#"{"document_type":"Letter"}"#)),
+            ("b.pdf", Some(r#"{"document_type":"Invoice"}"#)),
+            ("c.pdf", Some(r#"{"document_type":"Letter"}"#)),
+            ("d.pdf", Some(r#"{"date":"2024-01-01"}"#)),
+            ("e.pdf", Some("not json")),
+            ("f.pdf", None),
+        ] {
+            create_file(
+                &pool,
+                &case.id,
+                &format!("/p/{}", name),
+                name,
+                None,
+                metadata,
+                &FileHashes::default(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let types = list_document_types(&pool, &case.id).await.unwrap();
+        let types: Vec<(&str, i64)> = types
+            .iter()
+            .map(|t| (t.document_type.as_str(), t.count))
+            .collect();
+        assert_eq!(types, vec![("Letter", 2), ("Invoice", 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_case_stats() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Stats Case", "bundle", None)
+            .await
+            .unwrap();
+        assert_eq!(
+            case_stats(&pool, &case.id).await.unwrap(),
+            CaseStats::default()
+        );
+
+        let dir = std::env::temp_dir().join(format!("casepilot-stats-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let on_disk = dir.join("a.pdf");
+        std::fs::write(&on_disk, vec![0u8; 1234]).unwrap();
+        let paths = [
+            on_disk.to_string_lossy().into_owned(),
+            "/missing/b.pdf".to_string(),
+            "/missing/c.pdf".to_string(),
+        ];
+        let mut file_ids = Vec::new();
+        for (path, pages) in paths.iter().zip([Some(3), Some(12), None]) {
+            let file = create_file(
+                &pool,
+                &case.id,
+                path,
+                "doc.pdf",
+                pages,
+                None,
+                &FileHashes::default(),
+            )
+            .await
+            .unwrap();
+            file_ids.push(file.id);
+        }
+        for (order, file_id) in file_ids.iter().take(2).enumerate() {
+            create_entry(
+                &pool,
+                &case.id,
+                order as i32,
+                "file",
+                Some(file_id),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+        create_entry(&pool, &case.id, 2, "component", None, Some("{}"), None)
+            .await
+            .unwrap();
+
+        let stats = case_stats(&pool, &case.id).await.unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+        assert_eq!(
+            stats,
+            CaseStats {
+                document_count: 2,
+                file_count: 3,
+                total_pages: 15,
+                total_file_bytes: 1234,
+            }
+        );
+        assert!(matches!(
+            case_stats(&pool, "missing-id").await.unwrap_err(),
+            DbError::NotFound { entity: "Case", .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rename_case() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Smith v Jones", "bundle", None)
+            .await
+            .unwrap();
+
+        let renamed = rename_case(&pool, &case.id, "  Smith v Jones (Appeal) ")
+            .await
+            .unwrap();
+        assert_eq!(renamed.id, case.id);
+        assert_eq!(renamed.name, "Smith v Jones (Appeal)");
+        assert!(renamed.updated_at >= case.updated_at);
+        assert_eq!(
+            list_cases(&pool, None, None).await.unwrap().cases[0].name,
+            renamed.name
+        );
+
+        assert!(matches!(
+            rename_case(&pool, &case.id, "   ").await,
+            Err(DbError::Invalid(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rename_missing_case() {
+        let pool = setup_test_db().await;
+        let err = rename_case(&pool, "missing", "New Name").await.unwrap_err();
+        assert!(matches!(err, DbError::NotFound { entity: "Case", .. }));
+    }
+
+    #[tokio::test]
+    async fn test_trashed_case_cannot_be_changed() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Smith v Jones", "bundle", None)
+            .await
+            .unwrap();
+        delete_case(&pool, &case.id).await.unwrap();
+
+        let not_found = |result: Result<_, DbError>| {
+            matches!(result, Err(DbError::NotFound { entity: "Case", .. }))
+        };
+        assert!(not_found(
+            rename_case(&pool, &case.id, "New Name").await.map(|_| ())
+        ));
+        assert!(not_found(
+            create_file(
+                &pool,
+                &case.id,
+                "/p/a.pdf",
+                "a.pdf",
+                None,
+                None,
+                &FileHashes::default(),
+            )
+            .await
+            .map(|_| ())
+        ));
+        assert!(not_found(
+            crate::db::duplicate_case(&pool, &case.id, "Copy")
+                .await
+                .map(|_| ())
+        ));
+        assert!(not_found(
+            crate::db::load_case_archive(&pool, &case.id)
+                .await
+                .map(|_| ())
+        ));
+
+        restore_case(&pool, &case.id).await.unwrap();
+        assert_eq!(
+            rename_case(&pool, &case.id, "New Name").await.unwrap().name,
+            "New Name"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_files_of_trashed_case_cannot_be_changed() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Smith v Jones", "bundle", None)
+            .await
+            .unwrap();
+        let file = create_file(
+            &pool,
+            &case.id,
+            "/p/a.pdf",
+            "a.pdf",
+            Some(1),
+            None,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+        add_file_tag(&pool, &file.id, "draft").await.unwrap();
+        delete_case(&pool, &case.id).await.unwrap();
+
+        let not_found = |result: Result<_, DbError>| {
+            matches!(result, Err(DbError::NotFound { entity: "File", .. }))
+        };
+        assert!(not_found(get_file(&pool, &file.id).await.map(|_| ())));
+        assert!(not_found(
+            update_file(&pool, &file.id, Some(2), None)
+                .await
+                .map(|_| ())
+        ));
+        assert!(not_found(add_file_tag(&pool, &file.id, "final").await));
+        assert!(not_found(remove_file_tag(&pool, &file.id, "draft").await));
+        assert!(not_found(list_file_tags(&pool, &file.id).await.map(|_| ())));
+        assert!(not_found(
+            delete_files(&pool, std::slice::from_ref(&file.id))
+                .await
+                .map(|_| ())
+        ));
+        assert!(list_files(&pool, &case.id).await.unwrap().is_empty());
+        assert!(list_files_filtered(&pool, &case.id, &FileFilter::default())
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(list_files_by_tag(&pool, &case.id, "draft")
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Restoring the case brings its files back untouched
+        restore_case(&pool, &case.id).await.unwrap();
+        let restored = get_file(&pool, &file.id).await.unwrap();
+        assert_eq!(restored.page_count, Some(1));
+        assert_eq!(
+            list_file_tags(&pool, &file.id).await.unwrap(),
+            vec!["draft"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_last_output_dir() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Output Case", "bundle", None)
+            .await
+            .unwrap();
+        assert_eq!(get_last_output_dir(&pool, &case.id).await.unwrap(), None);
+
+        let dir = std::env::temp_dir().join(format!("casepilot-out-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let dir_str = dir.to_str().unwrap();
+
+        set_last_output_dir(&pool, &case.id, dir_str).await.unwrap();
+        assert_eq!(
+            get_last_output_dir(&pool, &case.id)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some(dir_str)
+        );
+
+        // A folder that has since been removed is not offered
+        std::fs::remove_dir_all(&dir).ok();
+        assert_eq!(get_last_output_dir(&pool, &case.id).await.unwrap(), None);
+
+        assert!(matches!(
+            set_last_output_dir(&pool, &case.id, dir_str).await,
+            Err(DbError::Invalid(_))
+        ));
+        assert!(matches!(
+            get_last_output_dir(&pool, "missing").await,
+            Err(DbError::NotFound { entity: "Case", .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_build_name_index_maps_bundle_pages() {
+        use crate::pdf::fixtures::{temp_pdf_path, write_text_pdf};
+        use crate::pdf::{build_name_index, NameIndexEntry};
+
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Index Case", "bundle", None)
+            .await
+            .unwrap();
+
+        let letter = temp_pdf_path("names-letter");
+        let invoice = temp_pdf_path("names-invoice");
+        write_text_pdf(&letter, &["Letter to Lim Holdings", "Signed by Tan Ah Kow"]);
+        write_text_pdf(&invoice, &["Invoice to Tan Ah Kow", "Total due"]);
+
+        let mut order = 0;
+        for path in [&letter, &invoice] {
+            let file = create_file(
+                &pool,
+                &case.id,
+                path.to_str().unwrap(),
+                "doc.pdf",
+                Some(2),
+                None,
+                &FileHashes::default(),
+            )
+            .await
+            .unwrap();
+            create_entry(&pool, &case.id, order, "file", Some(&file.id), None, None)
+                .await
+                .unwrap();
+            create_entry(
+                &pool,
+                &case.id,
+                order + 1,
+                "component",
+                None,
+                Some("{}"),
+                None,
+            )
+            .await
+            .unwrap();
+            order += 2;
+        }
+
+        let sources = name_index_sources(&pool, &case.id).await.unwrap();
+        let index = build_name_index(&sources, &[]);
+        let excluded = build_name_index(&sources, &["lim holdings".to_string()]);
+        std::fs::remove_file(&letter).ok();
+        std::fs::remove_file(&invoice).ok();
+
+        assert_eq!(
+            index,
+            vec![
+                NameIndexEntry {
+                    name: "Lim Holdings".to_string(),
+                    pages: vec![1]
+                },
+                NameIndexEntry {
+                    name: "Tan Ah Kow".to_string(),
+                    pages: vec![2, 3]
+                },
+            ]
+        );
+        assert_eq!(excluded.len(), 1);
+        assert_eq!(excluded[0].name, "Tan Ah Kow");
+    }
+
+    #[tokio::test]
+    async fn test_estimate_compile_time() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Timing Case", "bundle", None)
+            .await
+            .unwrap();
+
+        // No history: default per-page rate
+        assert_eq!(
+            estimate_compile_time(&pool, 100).await.unwrap(),
+            Duration::from_millis(5_000)
+        );
+
+        // 1s of overhead plus 20ms a page
+        for pages in [50, 120, 300, 80] {
+            record_compile(&pool, &case.id, pages, 1_000 + 20 * u64::from(pages))
+                .await
+                .unwrap();
+        }
+
+        let small = estimate_compile_time(&pool, 100).await.unwrap();
+        let large = estimate_compile_time(&pool, 1_000).await.unwrap();
+        assert!((2_990..=3_010).contains(&small.as_millis()), "{:?}", small);
+        assert!(
+            (20_990..=21_010).contains(&large.as_millis()),
+            "{:?}",
+            large
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_tags() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Tag Case", "bundle", None)
+            .await
+            .unwrap();
+        let other_case = create_case(&pool, "Other Case", "bundle", None)
+            .await
+            .unwrap();
+        let draft = create_file(
+            &pool,
+            &case.id,
+            "/a.pdf",
+            "a.pdf",
+            None,
+            None,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+        let final_copy = create_file(
+            &pool,
+            &case.id,
+            "/b.pdf",
+            "b.pdf",
+            None,
+            None,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+        let elsewhere = create_file(
+            &pool,
+            &other_case.id,
+            "/c.pdf",
+            "c.pdf",
+            None,
+            None,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+
+        add_file_tag(&pool, &draft.id, "Draft").await.unwrap();
+        add_file_tag(&pool, &draft.id, " draft ").await.unwrap();
+        add_file_tag(&pool, &draft.id, "for-review").await.unwrap();
+        add_file_tag(&pool, &final_copy.id, "final").await.unwrap();
+        add_file_tag(&pool, &elsewhere.id, "draft").await.unwrap();
+
+        assert_eq!(
+            list_file_tags(&pool, &draft.id).await.unwrap(),
+            vec!["draft", "for-review"]
+        );
+        let drafts = list_files_by_tag(&pool, &case.id, "DRAFT").await.unwrap();
+        assert_eq!(
+            drafts.iter().map(|f| f.id.as_str()).collect::<Vec<_>>(),
+            vec![draft.id.as_str()]
+        );
+
+        remove_file_tag(&pool, &draft.id, "draft").await.unwrap();
+        assert!(list_files_by_tag(&pool, &case.id, "draft")
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            list_file_tags(&pool, &draft.id).await.unwrap(),
+            vec!["for-review"]
+        );
+
+        assert!(matches!(
+            add_file_tag(&pool, &draft.id, "  ").await,
+            Err(DbError::Invalid(_))
+        ));
+        assert!(matches!(
+            add_file_tag(&pool, "missing", "draft").await,
+            Err(DbError::NotFound { entity: "File", .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_ensure_page_counts() {
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Counts Case", "bundle", None)
+            .await
+            .unwrap();
+
+        let counted = create_file(
+            &pool,
+            &case.id,
+            "/p/a.pdf",
+            "a.pdf",
+            Some(3),
+            None,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+        let missing_late = create_file(
+            &pool,
+            &case.id,
+            "/p/b.pdf",
+            "b.pdf",
+            None,
+            None,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+        let missing_early = create_file(
+            &pool,
+            &case.id,
+            "/p/c.pdf",
+            "c.pdf",
+            None,
+            None,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+        // Not placed in any entry, so it doesn't block anything
+        create_file(
+            &pool,
+            &case.id,
+            "/p/d.pdf",
+            "d.pdf",
+            None,
+            None,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+
+        for (order, file) in [(0, &counted), (1, &missing_early), (2, &missing_late)] {
+            create_entry(&pool, &case.id, order, "file", Some(&file.id), None, None)
+                .await
+                .unwrap();
+        }
+
+        let missing = ensure_page_counts(&pool, &case.id).await.unwrap();
+        assert_eq!(
+            missing,
+            vec![missing_early.id.clone(), missing_late.id.clone()]
+        );
+
+        update_file(&pool, &missing_early.id, Some(2), None)
+            .await
+            .unwrap();
+        update_file(&pool, &missing_late.id, Some(5), None)
+            .await
+            .unwrap();
+        assert!(ensure_page_counts(&pool, &case.id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_find_file_by_hash() {
+        use crate::pdf::file_hashes;
+
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Hash Case", "bundle", None)
+            .await
+            .unwrap();
+
+        let dir = std::env::temp_dir().join(format!("casepilot-hash-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let first_path = dir.join("first.pdf");
+        let copy_path = dir.join("copy.pdf");
+        let other_path = dir.join("other.pdf");
+        std::fs::write(&first_path, b"%PDF-1.4 same bytes").unwrap();
+        std::fs::write(&copy_path, b"%PDF-1.4 same bytes").unwrap();
+        std::fs::write(&other_path, b"%PDF-1.4 different bytes").unwrap();
+
+        let mut created = Vec::new();
+        for path in [&first_path, &copy_path, &other_path] {
+            let path = path.to_str().unwrap();
+            created.push(
+                create_file(
+                    &pool,
+                    &case.id,
+                    path,
+                    "exhibit.pdf",
+                    None,
+                    None,
+                    &file_hashes(path),
+                )
+                .await
+                .unwrap(),
+            );
+        }
+        std::fs::remove_dir_all(&dir).ok();
+
+        let hash = created[0].content_hash.clone().unwrap();
+        assert_eq!(hash.len(), 64);
+        assert_eq!(created[1].content_hash.as_deref(), Some(hash.as_str()));
+        assert_ne!(created[2].content_hash.as_deref(), Some(hash.as_str()));
+
+        let found = find_file_by_hash(&pool, &case.id, &hash).await.unwrap();
+        assert_eq!(found.map(|f| f.id), Some(created[0].id.clone()));
+        assert!(find_file_by_hash(&pool, &case.id, "0000")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_find_visual_duplicates() {
+        use crate::pdf::file_hashes;
+        use crate::pdf::fixtures::{temp_pdf_path, write_scan_pdf, write_text_pdf};
+
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Scan Case", "bundle", None)
+            .await
+            .unwrap();
+
+        let gradient = |x: u32, y: u32| ((x * 7 + y * 2) % 256) as u8;
+        let original = temp_pdf_path("visual-original");
+        let rescan = temp_pdf_path("visual-rescan");
+        let other = temp_pdf_path("visual-other");
+        let text = temp_pdf_path("visual-text");
+        write_scan_pdf(&original, gradient);
+        write_scan_pdf(&rescan, |x, y| gradient(x, y).saturating_add(4));
+        write_scan_pdf(
+            &other,
+            |x, y| if (x / 4 + y / 4) % 2 == 0 { 0 } else { 255 },
+        );
+        write_text_pdf(&text, &["Typed letter"]);
+
+        let mut created = Vec::new();
+        for path in [&original, &rescan, &other, &text] {
+            created.push(
+                create_file(
+                    &pool,
+                    &case.id,
+                    path.to_str().unwrap(),
+                    "scan.pdf",
+                    Some(1),
+                    None,
+                    &file_hashes(path.to_str().unwrap()),
+                )
+                .await
+                .unwrap(),
+            );
+            std::fs::remove_file(path).ok();
+        }
+
+        assert!(created[0].visual_hash.is_some());
+        assert_eq!(created[3].visual_hash, None);
+
+        let groups = find_visual_duplicates(&pool, &case.id, 4).await.unwrap();
+        let ids: Vec<Vec<&str>> = groups
+            .iter()
+            .map(|g| g.iter().map(|f| f.id.as_str()).collect())
+            .collect();
+        assert_eq!(
+            ids,
+            vec![vec![created[0].id.as_str(), created[1].id.as_str()]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_relink_file() {
+        use crate::pdf::fixtures::{temp_pdf_path, write_text_pdf};
+        use crate::pdf::{file_hashes, validate_pdf};
+
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Relink Case", "bundle", None)
+            .await
+            .unwrap();
+
+        let original = temp_pdf_path("relink-original");
+        write_text_pdf(&original, &["Exhibit A"]);
+        let file = create_file(
+            &pool,
+            &case.id,
+            original.to_str().unwrap(),
+            "exhibit-a.pdf",
+            None,
+            None,
+            &file_hashes(original.to_str().unwrap()),
+        )
+        .await
+        .unwrap();
+
+        // Same bytes, new location
+        let moved = temp_pdf_path("relink-moved");
+        std::fs::rename(&original, &moved).unwrap();
+        let result = relink_file(
+            &pool,
+            &file.id,
+            moved.to_str().unwrap(),
+            &validate_pdf(moved.to_str().unwrap()),
+            &file_hashes(moved.to_str().unwrap()),
+        )
+        .await
+        .unwrap();
+        std::fs::remove_file(&moved).ok();
+
+        assert_eq!(result.warning, None);
+        assert_eq!(result.file.path, moved.to_str().unwrap());
+        assert_eq!(result.file.page_count, Some(1));
+        assert_eq!(result.file.content_hash, file.content_hash);
+
+        let missing = "/no/such/file.pdf";
+        assert!(matches!(
+            relink_file(
+                &pool,
+                &file.id,
+                missing,
+                &validate_pdf(missing),
+                &FileHashes::default()
+            )
+            .await,
+            Err(DbError::Invalid(_))
+        ));
+
+        // Files that don't open as PDFs are refused
+        let not_pdf = temp_pdf_path("relink-not-pdf");
+        std::fs::write(&not_pdf, b"Dear Sirs").unwrap();
+        let result = relink_file(
+            &pool,
+            &file.id,
+            not_pdf.to_str().unwrap(),
+            &validate_pdf(not_pdf.to_str().unwrap()),
+            &FileHashes::default(),
+        )
+        .await;
+        std::fs::remove_file(&not_pdf).ok();
+        assert!(
+            matches!(result, Err(DbError::Invalid(message)) if message.starts_with("Cannot relink"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_relink_file_with_different_content_warns() {
+        use crate::pdf::fixtures::{temp_pdf_path, write_text_pdf};
+        use crate::pdf::{file_hashes, validate_pdf};
+
+        let pool = setup_test_db().await;
+        let case = create_case(&pool, "Relink Case", "bundle", None)
+            .await
+            .unwrap();
+
+        let original = temp_pdf_path("relink-original");
+        write_text_pdf(&original, &["Exhibit A"]);
+        let file = create_file(
+            &pool,
+            &case.id,
+            original.to_str().unwrap(),
+            "exhibit-a.pdf",
+            Some(1),
+            None,
+            &file_hashes(original.to_str().unwrap()),
+        )
+        .await
+        .unwrap();
+
+        let replacement = temp_pdf_path("relink-replacement");
+        write_text_pdf(&replacement, &["Exhibit B", "Second page"]);
+        let result = relink_file(
+            &pool,
+            &file.id,
+            replacement.to_str().unwrap(),
+            &validate_pdf(replacement.to_str().unwrap()),
+            &file_hashes(replacement.to_str().unwrap()),
+        )
+        .await
+        .unwrap();
+        std::fs::remove_file(&original).ok();
+        std::fs::remove_file(&replacement).ok();
+
+        assert!(result.warning.unwrap().contains("exhibit-a.pdf"));
+        assert_eq!(result.file.page_count, Some(2));
+        assert_ne!(result.file.content_hash, file.content_hash);
+    }
+}
@@ -2,110 +2,380 @@
 
 use sqlx::{Pool, Sqlite};
 
-/// Run all database migrations
-pub async fn run_migrations(pool: &Pool<Sqlite>) -> Result<(), String> {
-    // Enable foreign keys
-    sqlx::query("PRAGMA foreign_keys = ON")
-        .execute(pool)
-        .await
-        .map_err(|e| format!("Failed to enable foreign keys: {}", e))?;
+use super::DbError;
 
-    // Check if we need to migrate from old schema (cases table without case_type column)
-    let has_case_type: bool = sqlx::query_scalar::<_, i32>(
-        "SELECT COUNT(*) FROM pragma_table_info('cases') WHERE name = 'case_type'",
-    )
-    .fetch_one(pool)
-    .await
-    .map(|count| count > 0)
-    .unwrap_or(false);
+/// A schema change applied exactly once, in version order
+struct Migration {
+    version: i64,
+    description: &'static str,
+    /// Skip the statements (but still record the version) when this column
+    /// already exists, for databases created before migrations were versioned
+    unless_column: Option<(&'static str, &'static str)>,
+    statements: &'static [&'static str],
+}
 
-    // If old schema exists without case_type, drop everything and start fresh
-    if !has_case_type {
-        let cases_exists: bool = sqlx::query_scalar::<_, i32>(
-            "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='cases'",
-        )
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "create core tables",
+        unless_column: None,
+        statements: &[
+            // Cases: Top-level container (IS an Affidavit or Bundle)
+            r#"
+            CREATE TABLE IF NOT EXISTS cases (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+            "#,
+            // Files: Raw PDF assets
+            r#"
+            CREATE TABLE IF NOT EXISTS files (
+                id TEXT PRIMARY KEY,
+                case_id TEXT NOT NULL,
+                path TEXT NOT NULL,
+                original_name TEXT NOT NULL,
+                page_count INTEGER,
+                metadata_json TEXT,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (case_id) REFERENCES cases(id) ON DELETE CASCADE
+            )
+            "#,
+            // Artifact Entries: Polymorphic links to cases
+            r#"
+            CREATE TABLE IF NOT EXISTS artifact_entries (
+                id TEXT PRIMARY KEY,
+                case_id TEXT NOT NULL,
+                sequence_order INTEGER NOT NULL,
+                row_type TEXT NOT NULL CHECK(row_type IN ('file', 'component')),
+                file_id TEXT,
+                config_json TEXT,
+                label_override TEXT,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (case_id) REFERENCES cases(id) ON DELETE CASCADE,
+                FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE
+            )
+            "#,
+        ],
+    },
+    Migration {
+        version: 2,
+        description: "add cases.case_type",
+        unless_column: Some(("cases", "case_type")),
+        // Cases from before affidavits existed were all bundles
+        statements: &[r#"
+            ALTER TABLE cases ADD COLUMN case_type TEXT NOT NULL DEFAULT 'bundle'
+                CHECK(case_type IN ('affidavit', 'bundle'))
+            "#],
+    },
+    Migration {
+        version: 3,
+        description: "add cases.content_json",
+        unless_column: Some(("cases", "content_json")),
+        statements: &["ALTER TABLE cases ADD COLUMN content_json TEXT"],
+    },
+    Migration {
+        version: 4,
+        description: "create app_settings and bundle_drafts",
+        unless_column: None,
+        statements: &[
+            // App Settings: Key/value store for user preferences (JSON values)
+            r#"
+            CREATE TABLE IF NOT EXISTS app_settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )
+            "#,
+            // Bundle Drafts: Serialized in-progress bundle builder state (one per case)
+            r#"
+            CREATE TABLE IF NOT EXISTS bundle_drafts (
+                case_id TEXT PRIMARY KEY,
+                draft_json TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (case_id) REFERENCES cases(id) ON DELETE CASCADE
+            )
+            "#,
+        ],
+    },
+    Migration {
+        version: 5,
+        description: "add files.content_hash",
+        unless_column: Some(("files", "content_hash")),
+        statements: &[
+            "ALTER TABLE files ADD COLUMN content_hash TEXT",
+            "CREATE INDEX IF NOT EXISTS idx_files_content_hash ON files(case_id, content_hash)",
+        ],
+    },
+    Migration {
+        version: 6,
+        description: "add files.visual_hash",
+        unless_column: Some(("files", "visual_hash")),
+        statements: &["ALTER TABLE files ADD COLUMN visual_hash TEXT"],
+    },
+    Migration {
+        version: 7,
+        description: "add cases.last_output_dir",
+        unless_column: Some(("cases", "last_output_dir")),
+        statements: &["ALTER TABLE cases ADD COLUMN last_output_dir TEXT"],
+    },
+    Migration {
+        version: 8,
+        description: "create compile_history",
+        unless_column: None,
+        statements: &[
+            // Compile History: Timing of past compiles, for ETAs
+            r#"
+            CREATE TABLE IF NOT EXISTS compile_history (
+                id TEXT PRIMARY KEY,
+                case_id TEXT NOT NULL,
+                page_count INTEGER NOT NULL,
+                duration_ms INTEGER NOT NULL,
+                compiled_at TEXT NOT NULL,
+                FOREIGN KEY (case_id) REFERENCES cases(id) ON DELETE CASCADE
+            )
+            "#,
+        ],
+    },
+    Migration {
+        version: 9,
+        description: "create file_tags",
+        unless_column: None,
+        statements: &[
+            // File Tags: Free-form labels on repository files ("draft", "final")
+            r#"
+            CREATE TABLE IF NOT EXISTS file_tags (
+                file_id TEXT NOT NULL,
+                tag TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (file_id, tag),
+                FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_file_tags_tag ON file_tags(tag)",
+        ],
+    },
+    Migration {
+        version: 10,
+        description: "create case_sections",
+        unless_column: None,
+        statements: &[
+            // Case Sections: Editor content split at headings for large affidavits
+            r#"
+            CREATE TABLE IF NOT EXISTS case_sections (
+                id TEXT PRIMARY KEY,
+                case_id TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                heading TEXT,
+                format TEXT NOT NULL CHECK(format IN ('tiptap', 'html')),
+                content TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (case_id) REFERENCES cases(id) ON DELETE CASCADE
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_case_sections_case ON case_sections(case_id, position)",
+        ],
+    },
+    Migration {
+        version: 11,
+        description: "add cases.deleted_at",
+        unless_column: Some(("cases", "deleted_at")),
+        // Set when a case is moved to the trash; NULL for live cases
+        statements: &["ALTER TABLE cases ADD COLUMN deleted_at TEXT"],
+    },
+];
+
+async fn column_exists(pool: &Pool<Sqlite>, table: &str, column: &str) -> Result<bool, DbError> {
+    sqlx::query_scalar::<_, i32>("SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?")
+        .bind(table)
+        .bind(column)
         .fetch_one(pool)
         .await
         .map(|count| count > 0)
-        .unwrap_or(false);
+        .map_err(DbError::query("Failed to inspect schema"))
+}
 
-        if cases_exists {
-            sqlx::query("DROP TABLE IF EXISTS artifact_entries")
-                .execute(pool)
-                .await
-                .ok();
-            sqlx::query("DROP TABLE IF EXISTS files")
-                .execute(pool)
-                .await
-                .ok();
-            sqlx::query("DROP TABLE IF EXISTS artifacts")
-                .execute(pool)
-                .await
-                .ok();
-            sqlx::query("DROP TABLE IF EXISTS cases")
-                .execute(pool)
-                .await
-                .ok();
-        }
-    }
+/// Run all pending database migrations
+pub async fn run_migrations(pool: &Pool<Sqlite>) -> Result<(), DbError> {
+    // Enable foreign keys
+    sqlx::query("PRAGMA foreign_keys = ON")
+        .execute(pool)
+        .await
+        .map_err(DbError::query("Failed to enable foreign keys"))?;
 
-    // Cases: Top-level container (IS an Affidavit or Bundle)
     sqlx::query(
         r#"
-        CREATE TABLE IF NOT EXISTS cases (
-            id TEXT PRIMARY KEY,
-            name TEXT NOT NULL,
-            case_type TEXT NOT NULL CHECK(case_type IN ('affidavit', 'bundle')),
-            content_json TEXT,
-            created_at TEXT NOT NULL,
-            updated_at TEXT NOT NULL
+        CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at TEXT NOT NULL
         )
         "#,
     )
     .execute(pool)
     .await
-    .map_err(|e| format!("Failed to create cases table: {}", e))?;
+    .map_err(DbError::query("Failed to create schema_version table"))?;
 
-    // Files: Raw PDF assets
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS files (
-            id TEXT PRIMARY KEY,
-            case_id TEXT NOT NULL,
-            path TEXT NOT NULL,
-            original_name TEXT NOT NULL,
-            page_count INTEGER,
-            metadata_json TEXT,
-            created_at TEXT NOT NULL,
-            FOREIGN KEY (case_id) REFERENCES cases(id) ON DELETE CASCADE
-        )
-        "#,
-    )
-    .execute(pool)
-    .await
-    .map_err(|e| format!("Failed to create files table: {}", e))?;
+    let current: i64 = sqlx::query_scalar("SELECT COALESCE(MAX(version), 0) FROM schema_version")
+        .fetch_one(pool)
+        .await
+        .map_err(DbError::query("Failed to read schema version"))?;
 
-    // Artifact Entries: Polymorphic links to cases
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS artifact_entries (
-            id TEXT PRIMARY KEY,
-            case_id TEXT NOT NULL,
-            sequence_order INTEGER NOT NULL,
-            row_type TEXT NOT NULL CHECK(row_type IN ('file', 'component')),
-            file_id TEXT,
-            config_json TEXT,
-            label_override TEXT,
-            created_at TEXT NOT NULL,
-            FOREIGN KEY (case_id) REFERENCES cases(id) ON DELETE CASCADE,
-            FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let already_applied = match migration.unless_column {
+            Some((table, column)) => column_exists(pool, table, column).await?,
+            None => false,
+        };
+
+        let context = format!(
+            "Failed to apply migration {} ({})",
+            migration.version, migration.description
+        );
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(DbError::query(context.clone()))?;
+
+        if !already_applied {
+            for statement in migration.statements {
+                sqlx::query(statement)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(DbError::query(context.clone()))?;
+            }
+        }
+
+        sqlx::query(
+            "INSERT INTO schema_version (version, description, applied_at) VALUES (?, ?, ?)",
         )
-        "#,
-    )
-    .execute(pool)
-    .await
-    .map_err(|e| format!("Failed to create artifact_entries table: {}", e))?;
+        .bind(migration.version)
+        .bind(migration.description)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(&mut *tx)
+        .await
+        .map_err(DbError::query(context.clone()))?;
+
+        tx.commit().await.map_err(DbError::query(context))?;
+    }
 
     Ok(())
 }
 
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn memory_pool() -> Pool<Sqlite> {
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database")
+    }
+
+    async fn applied_versions(pool: &Pool<Sqlite>) -> Vec<i64> {
+        sqlx::query_scalar("SELECT version FROM schema_version ORDER BY version")
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_fresh_database_records_every_version() {
+        let pool = memory_pool().await;
+        run_migrations(&pool).await.unwrap();
+
+        let expected: Vec<i64> = MIGRATIONS.iter().map(|m| m.version).collect();
+        assert_eq!(applied_versions(&pool).await, expected);
+        assert!(column_exists(&pool, "cases", "case_type").await.unwrap());
+        assert!(column_exists(&pool, "cases", "content_json").await.unwrap());
+
+        // Running again is a no-op
+        run_migrations(&pool).await.unwrap();
+        assert_eq!(applied_versions(&pool).await, expected);
+    }
+
+    #[tokio::test]
+    async fn test_v1_database_keeps_rows() {
+        let pool = memory_pool().await;
+
+        // A database from before case_type and versioned migrations
+        sqlx::query(
+            "CREATE TABLE cases (id TEXT PRIMARY KEY, name TEXT NOT NULL,
+             created_at TEXT NOT NULL, updated_at TEXT NOT NULL)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        for (id, name) in [("c1", "Tan v Lim"), ("c2", "Re Estate of Wong")] {
+            sqlx::query("INSERT INTO cases VALUES (?, ?, '2024-01-01', '2024-01-01')")
+                .bind(id)
+                .bind(name)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        run_migrations(&pool).await.unwrap();
+
+        let rows: Vec<(String, String, String, Option<String>)> =
+            sqlx::query_as("SELECT id, name, case_type, content_json FROM cases ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                ("c1".into(), "Tan v Lim".into(), "bundle".into(), None),
+                (
+                    "c2".into(),
+                    "Re Estate of Wong".into(),
+                    "bundle".into(),
+                    None
+                ),
+            ]
+        );
+        assert_eq!(
+            applied_versions(&pool).await,
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unversioned_current_database_is_adopted() {
+        let pool = memory_pool().await;
+
+        // Tables created by the pre-versioning run_migrations
+        sqlx::query(
+            "CREATE TABLE cases (id TEXT PRIMARY KEY, name TEXT NOT NULL,
+             case_type TEXT NOT NULL CHECK(case_type IN ('affidavit', 'bundle')),
+             content_json TEXT, created_at TEXT NOT NULL, updated_at TEXT NOT NULL)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO cases VALUES ('c1', 'Affidavit of JT', 'affidavit', '{}', 'x', 'x')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        run_migrations(&pool).await.unwrap();
+
+        let case_type: String = sqlx::query_scalar("SELECT case_type FROM cases WHERE id = 'c1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(case_type, "affidavit");
+        assert_eq!(
+            applied_versions(&pool).await,
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]
+        );
+    }
+}
@@ -0,0 +1,367 @@
+//! Sectioned storage of case content for large affidavits
+//!
+//! A case's editor content can be split at its headings into rows of
+//! `case_sections`, so saving an edit only writes the section that changed.
+//! Sections are reassembled into a single editor string for export.
+
+use serde_json::Value;
+use sqlx::{Pool, Sqlite};
+
+use super::DbError;
+use crate::CaseSection;
+
+/// One section before it is stored: heading text, format and content
+type SplitSection = (Option<String>, &'static str, String);
+
+/// Concatenated text of a TipTap node and its descendants
+fn node_text(node: &Value) -> String {
+    let mut text = node
+        .get("text")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    if let Some(children) = node.get("content").and_then(Value::as_array) {
+        for child in children {
+            text.push_str(&node_text(child));
+        }
+    }
+    text
+}
+
+/// Split a TipTap document's top-level nodes at each heading. Each section
+/// stores its nodes as a JSON array, the heading node first.
+fn split_tiptap(doc: &Value) -> Vec<SplitSection> {
+    let mut sections: Vec<(Option<String>, Vec<Value>)> = Vec::new();
+    for node in doc
+        .get("content")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let is_heading = node.get("type").and_then(Value::as_str) == Some("heading");
+        if is_heading || sections.is_empty() {
+            let heading = is_heading.then(|| node_text(node));
+            sections.push((heading, Vec::new()));
+        }
+        if let Some((_, nodes)) = sections.last_mut() {
+            nodes.push(node.clone());
+        }
+    }
+    sections
+        .into_iter()
+        .map(|(heading, nodes)| (heading, "tiptap", Value::Array(nodes).to_string()))
+        .collect()
+}
+
+/// Byte offset of the next `<h1>`-`<h6>` opening tag at or after `from`
+fn next_heading_tag(html: &str, from: usize) -> Option<usize> {
+    let bytes = html.as_bytes();
+    (from..bytes.len().saturating_sub(2)).find(|&i| {
+        bytes[i] == b'<'
+            && bytes[i + 1].eq_ignore_ascii_case(&b'h')
+            && (b'1'..=b'6').contains(&bytes[i + 2])
+    })
+}
+
+/// Text inside the heading element starting at the beginning of `html`
+fn html_heading_text(html: &str) -> String {
+    let Some(open_end) = html.find('>') else {
+        return String::new();
+    };
+    let inner = &html[open_end + 1..];
+    let inner = &inner[..inner
+        .to_ascii_lowercase()
+        .find("</h")
+        .unwrap_or(inner.len())];
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for ch in inner.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.trim().to_string()
+}
+
+/// Split HTML at each heading tag; sections concatenate back to the original
+fn split_html(html: &str) -> Vec<SplitSection> {
+    let mut starts = vec![0];
+    let mut from = 1;
+    while let Some(start) = next_heading_tag(html, from) {
+        starts.push(start);
+        from = start + 1;
+    }
+    starts.push(html.len());
+
+    starts
+        .windows(2)
+        .filter(|w| w[0] < w[1])
+        .map(|w| {
+            let part = &html[w[0]..w[1]];
+            let heading = next_heading_tag(part, 0)
+                .filter(|&i| i == 0)
+                .map(|_| html_heading_text(part));
+            (heading, "html", part.to_string())
+        })
+        .collect()
+}
+
+/// The editor content string held in a case's content_json
+fn editor_content(content_json: &str) -> Option<String> {
+    let root: Value = serde_json::from_str(content_json).ok()?;
+    match root.get("content")? {
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn split_content(content: &str) -> Vec<SplitSection> {
+    match serde_json::from_str::<Value>(content) {
+        Ok(doc) if doc.is_object() => split_tiptap(&doc),
+        _ if content.is_empty() => Vec::new(),
+        _ => split_html(content),
+    }
+}
+
+pub async fn list_case_sections(
+    pool: &Pool<Sqlite>,
+    case_id: &str,
+) -> Result<Vec<CaseSection>, DbError> {
+    sqlx::query_as::<_, CaseSection>(
+        "SELECT id, case_id, position, heading, format, content, updated_at
+         FROM case_sections WHERE case_id = ? ORDER BY position ASC",
+    )
+    .bind(case_id)
+    .fetch_all(pool)
+    .await
+    .map_err(DbError::query("Failed to list case sections"))
+}
+
+/// Split a case's editor content into sections at its headings, replacing
+/// any sections stored before. Returns the new sections in order.
+pub async fn split_case_content(
+    pool: &Pool<Sqlite>,
+    case_id: &str,
+) -> Result<Vec<CaseSection>, DbError> {
+    let content_json: Option<String> =
+        sqlx::query_scalar("SELECT content_json FROM cases WHERE id = ?")
+            .bind(case_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(DbError::query("Failed to load case content"))?
+            .ok_or_else(|| DbError::not_found("Case", case_id))?;
+    let content = content_json
+        .as_deref()
+        .and_then(editor_content)
+        .unwrap_or_default();
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(DbError::query("Failed to split case content"))?;
+    sqlx::query("DELETE FROM case_sections WHERE case_id = ?")
+        .bind(case_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(DbError::query("Failed to clear case sections"))?;
+    for (position, (heading, format, content)) in split_content(&content).into_iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO case_sections (id, case_id, position, heading, format, content, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(case_id)
+        .bind(position as i32)
+        .bind(heading)
+        .bind(format)
+        .bind(content)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(DbError::query("Failed to save case section"))?;
+    }
+    tx.commit()
+        .await
+        .map_err(DbError::query("Failed to split case content"))?;
+
+    list_case_sections(pool, case_id).await
+}
+
+/// Replace one section's content, leaving the case's other sections untouched
+pub async fn update_case_section(
+    pool: &Pool<Sqlite>,
+    id: &str,
+    heading: Option<&str>,
+    content: &str,
+) -> Result<CaseSection, DbError> {
+    let format: String = sqlx::query_scalar("SELECT format FROM case_sections WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(DbError::query("Failed to load case section"))?
+        .ok_or_else(|| DbError::not_found("Section", id))?;
+    if format == "tiptap"
+        && !serde_json::from_str::<Value>(content).is_ok_and(|nodes| nodes.is_array())
+    {
+        return Err(DbError::Invalid(
+            "Section content must be a JSON array of editor nodes".to_string(),
+        ));
+    }
+
+    sqlx::query("UPDATE case_sections SET heading = ?, content = ?, updated_at = ? WHERE id = ?")
+        .bind(heading)
+        .bind(content)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(DbError::query("Failed to update case section"))?;
+
+    sqlx::query_as::<_, CaseSection>(
+        "SELECT id, case_id, position, heading, format, content, updated_at
+         FROM case_sections WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_one(pool)
+    .await
+    .map_err(DbError::query("Failed to load case section"))
+}
+
+/// Reassemble a case's sections into one editor content string
+pub async fn assemble_case_content(pool: &Pool<Sqlite>, case_id: &str) -> Result<String, DbError> {
+    let sections = list_case_sections(pool, case_id).await?;
+    if sections.iter().all(|s| s.format == "html") {
+        return Ok(sections.into_iter().map(|s| s.content).collect());
+    }
+
+    let mut nodes = Vec::new();
+    for section in sections {
+        match serde_json::from_str::<Value>(&section.content) {
+            Ok(Value::Array(items)) => nodes.extend(items),
+            _ => {
+                return Err(DbError::Invalid(format!(
+                    "Section {} does not hold editor nodes",
+                    section.id
+                )))
+            }
+        }
+    }
+    Ok(serde_json::json!({ "type": "doc", "content": nodes }).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::queries::create_case;
+    use crate::db::schema::run_migrations;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> Pool<Sqlite> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+
+        run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        pool
+    }
+
+    fn heading(text: &str) -> Value {
+        serde_json::json!({
+            "type": "heading",
+            "attrs": { "level": 2 },
+            "content": [{ "type": "text", "text": text }]
+        })
+    }
+
+    fn paragraph(text: &str) -> Value {
+        serde_json::json!({ "type": "paragraph", "content": [{ "type": "text", "text": text }] })
+    }
+
+    #[tokio::test]
+    async fn test_split_and_reassemble_tiptap() {
+        let pool = setup_test_db().await;
+        let doc = serde_json::json!({
+            "type": "doc",
+            "content": [
+                paragraph("I, Tan Ah Kow, affirm as follows:"),
+                heading("Background"),
+                paragraph("The claimant is a director."),
+                heading("The Agreement"),
+                paragraph("On 1 March the parties met."),
+                paragraph("Terms were agreed."),
+            ]
+        });
+        let content_json = serde_json::json!({ "content": doc.to_string() }).to_string();
+        let case = create_case(&pool, "Affidavit", "affidavit", Some(&content_json))
+            .await
+            .unwrap();
+
+        let sections = split_case_content(&pool, &case.id).await.unwrap();
+        let headings: Vec<Option<&str>> = sections.iter().map(|s| s.heading.as_deref()).collect();
+        assert_eq!(
+            headings,
+            vec![None, Some("Background"), Some("The Agreement")]
+        );
+
+        let assembled = assemble_case_content(&pool, &case.id).await.unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&assembled).unwrap(), doc);
+    }
+
+    #[tokio::test]
+    async fn test_split_and_reassemble_html() {
+        let pool = setup_test_db().await;
+        let html = "<p>Intro</p><h2>Background</h2><p>Facts</p><H2><strong>Relief</strong></H2><p>Sought</p>";
+        let content_json = serde_json::json!({ "content": html }).to_string();
+        let case = create_case(&pool, "Affidavit", "affidavit", Some(&content_json))
+            .await
+            .unwrap();
+
+        let sections = split_case_content(&pool, &case.id).await.unwrap();
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[2].heading.as_deref(), Some("Relief"));
+        assert_eq!(assemble_case_content(&pool, &case.id).await.unwrap(), html);
+    }
+
+    #[tokio::test]
+    async fn test_editing_one_section_leaves_others() {
+        let pool = setup_test_db().await;
+        let html = "<h1>One</h1><p>a</p><h1>Two</h1><p>b</p><h1>Three</h1><p>c</p>";
+        let content_json = serde_json::json!({ "content": html }).to_string();
+        let case = create_case(&pool, "Affidavit", "affidavit", Some(&content_json))
+            .await
+            .unwrap();
+        let before = split_case_content(&pool, &case.id).await.unwrap();
+
+        let edited = update_case_section(&pool, &before[1].id, Some("Two"), "<h1>Two</h1><p>B</p>")
+            .await
+            .unwrap();
+        assert_eq!(edited.content, "<h1>Two</h1><p>B</p>");
+
+        let after = list_case_sections(&pool, &case.id).await.unwrap();
+        for i in [0, 2] {
+            assert_eq!(after[i].content, before[i].content);
+            assert_eq!(after[i].updated_at, before[i].updated_at);
+        }
+        assert_eq!(
+            assemble_case_content(&pool, &case.id).await.unwrap(),
+            "<h1>One</h1><p>a</p><h1>Two</h1><p>B</p><h1>Three</h1><p>c</p>"
+        );
+
+        assert!(matches!(
+            update_case_section(&pool, "missing", None, "").await,
+            Err(DbError::NotFound {
+                entity: "Section",
+                ..
+            })
+        ));
+    }
+}
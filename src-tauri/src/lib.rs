@@ -8,11 +8,14 @@
 use serde::{Deserialize, Serialize};
 use sqlx::{sqlite::SqlitePoolOptions, FromRow, Pool, Sqlite};
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::Manager;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Notify};
 
+mod cache;
 mod commands;
 mod db;
+mod limiter;
 mod pdf;
 
 // ============================================================================
@@ -21,6 +24,49 @@ mod pdf;
 
 pub struct AppState {
     pub db: Arc<Mutex<Option<Pool<Sqlite>>>>,
+    /// Caps how many heavy PDF commands run at once
+    pub pdf_limiter: limiter::PdfLimiter,
+    /// Page counts of files already parsed this session
+    pub pdf_cache: Arc<cache::PdfCache>,
+    /// Signalled once startup has set up `db`
+    pub db_ready: Notify,
+}
+
+/// How long a command waits for startup to set up the database
+const DB_READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+impl AppState {
+    fn new() -> Self {
+        AppState {
+            db: Arc::new(Mutex::new(None)),
+            pdf_limiter: limiter::PdfLimiter::default(),
+            pdf_cache: Arc::default(),
+            db_ready: Notify::new(),
+        }
+    }
+
+    /// The database pool, waiting for startup to finish setting it up
+    pub async fn pool(&self) -> Result<Pool<Sqlite>, db::DbError> {
+        // Register before checking so a pool set in between still wakes us
+        let ready = self.db_ready.notified();
+        if let Some(pool) = self.db.lock().await.as_ref() {
+            return Ok(pool.clone());
+        }
+        tokio::time::timeout(DB_READY_TIMEOUT, ready)
+            .await
+            .map_err(|_| db::DbError::NotInitialized)?;
+        self.db
+            .lock()
+            .await
+            .clone()
+            .ok_or(db::DbError::NotInitialized)
+    }
+
+    /// Store the pool and wake every command waiting for it
+    pub async fn set_pool(&self, pool: Pool<Sqlite>) {
+        *self.db.lock().await = Some(pool);
+        self.db_ready.notify_waiters();
+    }
 }
 
 // ============================================================================
@@ -45,6 +91,10 @@ pub struct File {
     pub original_name: String,
     pub page_count: Option<i32>,
     pub metadata_json: Option<String>,
+    /// SHA-256 of the file bytes at import, hex encoded
+    pub content_hash: Option<String>,
+    /// Perceptual hash of the first page's scan, hex encoded
+    pub visual_hash: Option<String>,
     pub created_at: String,
 }
 
@@ -60,6 +110,19 @@ pub struct ArtifactEntry {
     pub created_at: String,
 }
 
+/// A slice of a case's editor content, from one heading to the next
+#[derive(Debug, Serialize, Deserialize, Clone, FromRow)]
+pub struct CaseSection {
+    pub id: String,
+    pub case_id: String,
+    pub position: i32,
+    pub heading: Option<String>,
+    pub format: String, // "tiptap" | "html"
+    /// TipTap: JSON array of top-level nodes. HTML: the raw fragment.
+    pub content: String,
+    pub updated_at: String,
+}
+
 // ============================================================================
 // REQUEST TYPES
 // ============================================================================
@@ -87,6 +150,16 @@ pub struct UpdateFileRequest {
     pub metadata_json: Option<String>,
 }
 
+/// Narrows a case's file list; unset fields match every file
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct FileFilter {
+    /// Detected `document_type` from the file's metadata, matched case-insensitively
+    pub document_type: Option<String>,
+    /// Inclusive import-date bounds as `YYYY-MM-DD`
+    pub created_from: Option<String>,
+    pub created_to: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateEntryRequest {
     pub case_id: String,
@@ -111,6 +184,81 @@ pub struct ReorderEntriesRequest {
     pub entry_ids: Vec<String>,
 }
 
+// ============================================================================
+// SETTINGS TYPES
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DescriptionLimitMode {
+    /// Cut over-long descriptions at the last word boundary
+    Truncate,
+    /// Refuse to store over-long descriptions
+    Reject,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DescriptionLimit {
+    pub max_length: usize,
+    pub mode: DescriptionLimitMode,
+}
+
+// ============================================================================
+// RESPONSE TYPES
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContentValidationResult {
+    pub ok: bool,
+    pub issues: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, FromRow)]
+pub struct MetadataFieldValue {
+    pub file_id: String,
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RelinkResult {
+    pub file: File,
+    /// Set when the new file's content differs from what was imported
+    pub warning: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CasePage {
+    pub cases: Vec<Case>,
+    /// Every live case, ignoring limit and offset, for page controls
+    pub total: i64,
+}
+
+/// Totals for a case's dashboard
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq)]
+pub struct CaseStats {
+    /// File entries placed in the case's bundle
+    pub document_count: i64,
+    pub file_count: i64,
+    pub total_pages: i64,
+    /// Size on disk of the case's files; missing files count as zero
+    pub total_file_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CaseExportResult {
+    /// Number of PDFs written into the archive
+    pub file_count: usize,
+    /// Files that couldn't be read and were left out
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, FromRow)]
+pub struct DocumentTypeCount {
+    #[serde(rename = "type")]
+    pub document_type: String,
+    pub count: i64,
+}
+
 // ============================================================================
 // PDF TYPES
 // ============================================================================
@@ -119,7 +267,11 @@ pub struct ReorderEntriesRequest {
 pub struct PdfMetadata {
     pub page_count: usize,
     pub title: Option<String>,
+    pub author: Option<String>,
+    pub creation_date: Option<String>,
     pub file_size: u64,
+    pub word_count: Option<usize>,
+    pub char_count: Option<usize>,
 }
 
 // ============================================================================
@@ -156,39 +308,127 @@ pub fn run() {
                     .expect("Failed to run migrations");
 
                 let state: tauri::State<AppState> = app_handle.state();
-                let mut db_guard = state.db.lock().await;
-                *db_guard = Some(pool);
+                state.set_pool(pool).await;
 
                 println!("Database initialized at: {}", db_path.display());
             });
 
             Ok(())
         })
-        .manage(AppState {
-            db: Arc::new(Mutex::new(None)),
-        })
+        .manage(AppState::new())
         .invoke_handler(tauri::generate_handler![
             // Case commands
+            commands::wait_for_db,
             commands::list_cases,
             commands::create_case,
+            commands::rename_case,
+            commands::set_last_output_dir,
+            commands::get_last_output_dir,
+            commands::record_compile,
+            commands::estimate_compile_time,
+            commands::build_name_index,
             commands::delete_case,
+            commands::duplicate_case,
+            commands::export_case,
+            commands::import_case,
+            commands::list_trashed_cases,
+            commands::restore_case,
+            commands::purge_case,
+            commands::case_stats,
+            commands::validate_content_json,
+            commands::normalize_case_content,
+            commands::migrate_case_content,
+            commands::migrate_all_content,
+            commands::split_case_content,
+            commands::list_case_sections,
+            commands::update_case_section,
+            commands::assemble_case_content,
+            commands::save_bundle_draft,
+            commands::load_bundle_draft,
             // File commands
             commands::list_files,
+            commands::list_files_in_bundle_order,
+            commands::collect_metadata_field,
+            commands::list_files_filtered,
+            commands::list_document_types,
+            commands::ensure_page_counts,
             commands::create_file,
             commands::get_file,
+            commands::find_file_by_hash,
+            commands::find_visual_duplicates,
+            commands::relink_file,
             commands::update_file,
             commands::delete_file,
+            commands::delete_files,
+            commands::add_file_tag,
+            commands::remove_file_tag,
+            commands::list_file_tags,
+            commands::list_files_by_tag,
             // Entry commands
             commands::list_entries,
             commands::create_entry,
             commands::update_entry,
             commands::delete_entry,
             commands::reorder_entries,
+            // Settings commands
+            commands::get_description_limit,
+            commands::set_description_limit,
+            commands::set_pdf_concurrency,
+            commands::backup_database,
             // PDF commands
             commands::extract_pdf_metadata,
+            commands::get_page_count,
+            commands::render_page_thumbnail,
+            commands::ocr_document,
             commands::extract_document_info,
+            commands::extract_document_info_with_rules,
+            commands::get_default_type_rules,
             commands::generate_auto_description,
+            commands::detect_optional_content,
+            commands::flatten_optional_content,
+            commands::self_check_pdf,
+            commands::generate_endorsement_page,
+            commands::append_endorsement_page,
+            commands::generate_cover_pdf,
+            commands::prepend_cover_page,
+            commands::generate_certificate,
+            commands::extract_text_with_positions,
+            commands::detect_stamp_collision,
+            commands::inject_bates_numbers,
+            commands::inject_exhibit_stamp,
+            commands::write_name_index_pdf,
+            commands::page_phash,
+            commands::validate_pdf,
+            commands::validate_folder,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_command_waits_for_db_initialization() {
+        let state = AppState::new();
+
+        let startup = async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let pool = SqlitePoolOptions::new()
+                .max_connections(1)
+                .connect("sqlite::memory:")
+                .await
+                .unwrap();
+            db::run_migrations(&pool).await.unwrap();
+            state.set_pool(pool).await;
+        };
+        let command = async {
+            let pool = state.pool().await?;
+            db::list_cases(&pool, None, None).await
+        };
+
+        let ((), cases) = tokio::join!(startup, command);
+        assert!(cases.unwrap().cases.is_empty());
+    }
+}
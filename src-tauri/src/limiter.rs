@@ -0,0 +1,109 @@
+//! Concurrency limit for heavy PDF operations
+//!
+//! Importing many files fires a burst of PDF commands at once. Each one loads
+//! a whole document into memory, so they queue on a shared semaphore.
+
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Concurrent PDF operations allowed until the user changes it
+pub const DEFAULT_PDF_CONCURRENCY: usize = 4;
+
+pub struct PdfLimiter {
+    semaphore: std::sync::Mutex<Arc<Semaphore>>,
+}
+
+impl PdfLimiter {
+    pub fn new(limit: usize) -> Self {
+        PdfLimiter {
+            semaphore: std::sync::Mutex::new(Arc::new(Semaphore::new(limit.max(1)))),
+        }
+    }
+
+    /// Wait for a free slot; the slot is released when the permit drops.
+    /// Commands go through [`PdfLimiter::run`] so the work leaves the runtime.
+    async fn acquire(&self) -> OwnedSemaphorePermit {
+        let semaphore = self.semaphore.lock().unwrap().clone();
+        semaphore
+            .acquire_owned()
+            .await
+            .expect("PDF semaphore is never closed")
+    }
+
+    /// Run blocking PDF work on the blocking thread pool once a slot is free,
+    /// keeping the async runtime free while it runs
+    pub async fn run<T, F>(&self, work: F) -> T
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let _permit = self.acquire().await;
+        tokio::task::spawn_blocking(work)
+            .await
+            .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+    }
+
+    /// Change the limit for operations that start from now on.
+    /// Operations already running finish under the old limit.
+    pub fn set_limit(&self, limit: usize) -> Result<(), String> {
+        if limit == 0 {
+            return Err("PDF concurrency must be at least 1".to_string());
+        }
+        *self.semaphore.lock().unwrap() = Arc::new(Semaphore::new(limit));
+        Ok(())
+    }
+}
+
+impl Default for PdfLimiter {
+    fn default() -> Self {
+        PdfLimiter::new(DEFAULT_PDF_CONCURRENCY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Run `tasks` operations at once and return the most that overlapped
+    async fn peak_concurrency(limiter: Arc<PdfLimiter>, tasks: usize) -> usize {
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..tasks)
+            .map(|_| {
+                let (limiter, running, peak) = (limiter.clone(), running.clone(), peak.clone());
+                tokio::spawn(async move {
+                    let _permit = limiter.acquire().await;
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        peak.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_operations_beyond_limit_wait() {
+        let limiter = Arc::new(PdfLimiter::new(2));
+        assert_eq!(peak_concurrency(limiter.clone(), 8).await, 2);
+
+        limiter.set_limit(1).unwrap();
+        assert_eq!(peak_concurrency(limiter.clone(), 4).await, 1);
+
+        assert!(limiter.set_limit(0).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_returns_result_and_releases_slot() {
+        let limiter = PdfLimiter::new(1);
+        assert_eq!(limiter.run(|| 2 + 2).await, 4);
+        assert_eq!(limiter.run(|| "again").await, "again");
+    }
+}
@@ -0,0 +1,87 @@
+//! Bates numbering for discovery bundles
+//!
+//! Stamps each page with a sequential identifier such as "ABC-000123".
+//! Numbering continues across files by passing the returned next number
+//! as the start for the following file.
+
+use lopdf::Document;
+use serde::{Deserialize, Serialize};
+
+use super::collision::StampCorner;
+use super::stamp::stamp_page;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatesStyle {
+    /// Printed before the number, including any separator ("ABC-")
+    pub prefix: String,
+    /// Minimum digits; shorter numbers are zero-padded
+    pub width: usize,
+    #[serde(default = "default_bates_corner")]
+    pub corner: StampCorner,
+}
+
+fn default_bates_corner() -> StampCorner {
+    StampCorner::BottomRight
+}
+
+impl BatesStyle {
+    pub fn format(&self, number: u64) -> String {
+        format!("{}{:0width$}", self.prefix, number, width = self.width)
+    }
+}
+
+/// Stamp every page of `input_path` with a Bates number, counting up from
+/// `start`. Returns the number the next file should start from.
+pub fn inject_bates_numbers(
+    input_path: &str,
+    output_path: &str,
+    start: u64,
+    style: &BatesStyle,
+) -> Result<u64, String> {
+    let mut doc = Document::load(input_path).map_err(|e| format!("Failed to load PDF: {}", e))?;
+
+    let mut number = start;
+    for page_id in doc.page_iter().collect::<Vec<_>>() {
+        stamp_page(&mut doc, page_id, style.corner, &style.format(number))?;
+        number += 1;
+    }
+
+    doc.save(output_path)
+        .map_err(|e| format!("Failed to save PDF: {}", e))?;
+    Ok(number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::fixtures::{temp_pdf_path, write_text_pdf};
+
+    #[test]
+    fn test_pages_get_sequential_padded_numbers() {
+        let input = temp_pdf_path("bates-in");
+        let output = temp_pdf_path("bates-out");
+        write_text_pdf(&input, &["One", "Two", "Three"]);
+        let style = BatesStyle {
+            prefix: "ABC-".to_string(),
+            width: 6,
+            corner: StampCorner::BottomRight,
+        };
+
+        let next = inject_bates_numbers(
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+            121,
+            &style,
+        )
+        .unwrap();
+        let doc = Document::load(&output).unwrap();
+        let third = doc.get_pages()[&3];
+        let content = String::from_utf8_lossy(&doc.get_page_content(third).unwrap()).into_owned();
+        std::fs::remove_file(&input).ok();
+        std::fs::remove_file(&output).ok();
+
+        assert_eq!(next, 124);
+        assert!(content.contains("(ABC-000123) Tj"));
+        assert_eq!(style.format(1234567), "ABC-1234567");
+    }
+}
@@ -0,0 +1,219 @@
+//! Certificate of accuracy for filed bundles
+//!
+//! A signed statement that the bundle is a true copy, added as the first or
+//! last page. The page total printed on it includes the certificate itself.
+
+use lopdf::{Document, Object};
+use serde::{Deserialize, Serialize};
+
+use super::endorsement::{encode_pdf_text, insert_text_page, media_box_size, A4_MEDIA_BOX};
+
+/// Used when no statement is configured
+pub const DEFAULT_CERTIFICATE_STATEMENT: &str =
+    "I certify that this bundle is a true and complete copy of the documents it contains.";
+
+/// Characters per line before the statement wraps (12pt Helvetica on A4)
+const STATEMENT_LINE_CHARS: usize = 75;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CertificateInfo {
+    pub preparer: String,
+    pub firm: String,
+    pub date: String,
+    #[serde(default)]
+    pub statement: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CertificatePosition {
+    Front,
+    Back,
+}
+
+/// Greedy word wrap; words longer than `width` get a line of their own
+pub(super) fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+fn certificate_content(
+    info: &CertificateInfo,
+    total_pages: usize,
+    height: f32,
+) -> Result<Vec<u8>, String> {
+    let statement = info
+        .statement
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or(DEFAULT_CERTIFICATE_STATEMENT);
+
+    let mut content = format!(
+        "BT /F1 16 Tf 72 {} Td (CERTIFICATE OF ACCURACY) Tj ET\n",
+        height - 120.0
+    );
+    content.push_str(&format!("BT /F1 12 Tf 18 TL 72 {} Td\n", height - 168.0));
+    for line in wrap_words(statement, STATEMENT_LINE_CHARS) {
+        content.push_str(&format!("({}) Tj T*\n", encode_pdf_text(&line)?));
+    }
+    let details = [
+        String::new(),
+        format!("This bundle contains {} pages.", total_pages),
+        String::new(),
+        format!("Prepared by: {}", info.preparer),
+        format!("Firm: {}", info.firm),
+        format!("Date: {}", info.date),
+        String::new(),
+        String::new(),
+        "Signature: ______________________________".to_string(),
+    ];
+    for line in &details {
+        content.push_str(&format!("({}) Tj T*\n", encode_pdf_text(line)?));
+    }
+    content.push_str("ET\n");
+    Ok(content.into_bytes())
+}
+
+/// Add a certificate of accuracy to a compiled bundle as front or back matter.
+/// Returns the page total printed on the certificate.
+pub fn generate_certificate(
+    bundle_path: &str,
+    info: &CertificateInfo,
+    position: CertificatePosition,
+    output_path: &str,
+) -> Result<usize, String> {
+    let mut doc =
+        Document::load(bundle_path).map_err(|e| format!("Failed to load bundle: {}", e))?;
+
+    let pages = doc.get_pages();
+    let total_pages = pages.len() + 1;
+    let pages_id = doc
+        .catalog()
+        .and_then(|catalog| catalog.get(b"Pages"))
+        .and_then(Object::as_reference)
+        .map_err(|e| format!("Failed to find page tree: {}", e))?;
+
+    // Match the size of the neighbouring bundle page
+    let at_front = position == CertificatePosition::Front;
+    let neighbour = if at_front {
+        pages.values().next()
+    } else {
+        pages.values().last()
+    };
+    let media_box = neighbour
+        .and_then(|page_id| doc.get_dictionary(*page_id).ok())
+        .and_then(|page| page.get(b"MediaBox").ok())
+        .and_then(|media_box| media_box.as_array().ok())
+        .cloned()
+        .unwrap_or_else(|| A4_MEDIA_BOX.iter().map(|&v| v.into()).collect());
+    let (_, height) = media_box_size(&media_box);
+
+    let content = certificate_content(info, total_pages, height)?;
+    insert_text_page(&mut doc, pages_id, at_front, media_box, content)?;
+
+    doc.save(output_path)
+        .map_err(|e| format!("Failed to save bundle: {}", e))?;
+    Ok(total_pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::fixtures::{temp_pdf_path, write_text_pdf};
+    use crate::pdf::text::extract_page_text;
+
+    fn info(statement: Option<&str>) -> CertificateInfo {
+        CertificateInfo {
+            preparer: "Tan Ah Kow".to_string(),
+            firm: "Lee (Singapore) LLP".to_string(),
+            date: "16 October 2026".to_string(),
+            statement: statement.map(str::to_string),
+        }
+    }
+
+    /// The text of every page, in order, with the statement's wrapped lines
+    /// joined back up
+    fn page_texts(path: &std::path::Path) -> Vec<String> {
+        let doc = Document::load(path).unwrap();
+        doc.get_pages()
+            .values()
+            .map(|page_id| {
+                extract_page_text(&doc, *page_id)
+                    .unwrap()
+                    .replace('\n', " ")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_front_certificate_with_configured_statement() {
+        let bundle = temp_pdf_path("cert-bundle");
+        let output = temp_pdf_path("cert-out");
+        write_text_pdf(&bundle, &["Tab 1", "Tab 2"]);
+
+        let statement = "I, Tan Ah Kow, confirm that the documents in this bundle are true copies of the originals produced in discovery.";
+        let total = generate_certificate(
+            bundle.to_str().unwrap(),
+            &info(Some(statement)),
+            CertificatePosition::Front,
+            output.to_str().unwrap(),
+        )
+        .unwrap();
+        let texts = page_texts(&output);
+        std::fs::remove_file(&bundle).ok();
+        std::fs::remove_file(&output).ok();
+
+        assert_eq!(total, 3);
+        assert_eq!(texts.len(), 3);
+        assert!(texts[0].contains("CERTIFICATE OF ACCURACY"));
+        assert!(texts[0].contains(statement));
+        assert!(texts[0].contains("This bundle contains 3 pages."));
+        assert!(texts[1].contains("Tab 1"));
+    }
+
+    #[test]
+    fn test_back_certificate_uses_default_statement() {
+        let bundle = temp_pdf_path("cert-bundle");
+        let output = temp_pdf_path("cert-out");
+        write_text_pdf(&bundle, &["Tab 1", "Tab 2", "Tab 3"]);
+
+        let total = generate_certificate(
+            bundle.to_str().unwrap(),
+            &info(None),
+            CertificatePosition::Back,
+            output.to_str().unwrap(),
+        )
+        .unwrap();
+        let texts = page_texts(&output);
+        std::fs::remove_file(&bundle).ok();
+        std::fs::remove_file(&output).ok();
+
+        assert_eq!(total, 4);
+        assert!(texts[0].contains("Tab 1"));
+        assert!(texts[3].contains(DEFAULT_CERTIFICATE_STATEMENT));
+        assert!(texts[3].contains("This bundle contains 4 pages."));
+        assert!(texts[3].contains("Firm: Lee (Singapore) LLP"));
+    }
+
+    #[test]
+    fn test_wrap_words() {
+        assert_eq!(
+            wrap_words("one two three four", 9),
+            vec!["one two", "three", "four"]
+        );
+        assert_eq!(wrap_words("", 9), Vec::<String>::new());
+    }
+}
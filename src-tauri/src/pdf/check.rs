@@ -0,0 +1,102 @@
+//! Output sanity checks for compiled PDFs
+
+use super::text::extract_page_text;
+use lopdf::{Document, Object};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SelfCheckResult {
+    pub ok: bool,
+    pub issues: Vec<String>,
+}
+
+/// Reload a PDF and confirm every page resolves and yields text without errors
+pub fn self_check(pdf_path: &str) -> SelfCheckResult {
+    let mut issues = Vec::new();
+
+    let doc = match Document::load(pdf_path) {
+        Ok(doc) => doc,
+        Err(e) => {
+            return SelfCheckResult {
+                ok: false,
+                issues: vec![format!("Failed to load PDF: {}", e)],
+            }
+        }
+    };
+
+    let pages = doc.get_pages();
+    if pages.is_empty() {
+        issues.push("PDF has no pages".to_string());
+    }
+
+    let declared_count = doc
+        .catalog()
+        .and_then(|catalog| catalog.get(b"Pages"))
+        .and_then(Object::as_reference)
+        .and_then(|pages_id| doc.get_dictionary(pages_id))
+        .and_then(|pages| pages.get(b"Count"))
+        .and_then(Object::as_i64);
+    match declared_count {
+        Ok(count) if count as usize != pages.len() => issues.push(format!(
+            "Page tree declares {} pages but {} were found",
+            count,
+            pages.len()
+        )),
+        Ok(_) => {}
+        Err(e) => issues.push(format!("Failed to read page count: {}", e)),
+    }
+
+    for (page_number, page_id) in pages {
+        match doc.get_dictionary(page_id) {
+            Ok(page) if page.type_is(b"Page") => {}
+            Ok(_) => {
+                issues.push(format!("Page {} is not a page object", page_number));
+                continue;
+            }
+            Err(e) => {
+                issues.push(format!("Page {} does not resolve: {}", page_number, e));
+                continue;
+            }
+        }
+        if let Err(e) = extract_page_text(&doc, page_id) {
+            issues.push(format!("Page {}: {}", page_number, e));
+        }
+    }
+
+    SelfCheckResult {
+        ok: issues.is_empty(),
+        issues,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::fixtures::{temp_pdf_path, write_text_pdf};
+
+    #[test]
+    fn test_self_check_valid_pdf() {
+        let path = temp_pdf_path("self-check");
+        write_text_pdf(&path, &["Exhibit A", "Exhibit B", "Exhibit C"]);
+
+        let result = self_check(path.to_str().unwrap());
+        std::fs::remove_file(&path).ok();
+
+        assert!(result.ok, "unexpected issues: {:?}", result.issues);
+        assert!(result.issues.is_empty());
+    }
+
+    #[test]
+    fn test_self_check_truncated_pdf() {
+        let path = temp_pdf_path("self-check-truncated");
+        write_text_pdf(&path, &["Exhibit A", "Exhibit B", "Exhibit C"]);
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+
+        let result = self_check(path.to_str().unwrap());
+        std::fs::remove_file(&path).ok();
+
+        assert!(!result.ok);
+        assert!(!result.issues.is_empty());
+    }
+}
@@ -0,0 +1,103 @@
+//! Stamp collision detection
+//!
+//! Before a page number or exhibit stamp is injected, checks whether the
+//! corner it would occupy already holds text such as a letterhead reference.
+
+use lopdf::{Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+
+use super::positions::{page_text_spans, TextSpan};
+
+/// Size of the area a stamp occupies, in points
+const STAMP_WIDTH: f32 = 144.0;
+const STAMP_HEIGHT: f32 = 36.0;
+
+/// Distance from the stamp area to the page edges, in points
+pub(super) const STAMP_MARGIN: f32 = 18.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StampCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// A page's MediaBox as `[x0, y0, x1, y1]`, following inherited values
+pub(super) fn page_media_box(doc: &Document, page_id: ObjectId) -> Option<[f32; 4]> {
+    let mut node = doc.get_dictionary(page_id).ok()?;
+    loop {
+        if let Ok(media_box) = node.get(b"MediaBox").and_then(Object::as_array) {
+            let value = |i: usize| media_box.get(i).and_then(|o| o.as_float().ok());
+            return Some([value(0)?, value(1)?, value(2)?, value(3)?]);
+        }
+        let parent = node.get(b"Parent").and_then(Object::as_reference).ok()?;
+        node = doc.get_dictionary(parent).ok()?;
+    }
+}
+
+/// The stamp area for a corner of a page, as `[x0, y0, x1, y1]`
+pub(super) fn stamp_region(media_box: [f32; 4], corner: StampCorner) -> [f32; 4] {
+    let [left, bottom, right, top] = media_box;
+    let x0 = match corner {
+        StampCorner::TopLeft | StampCorner::BottomLeft => left + STAMP_MARGIN,
+        StampCorner::TopRight | StampCorner::BottomRight => right - STAMP_MARGIN - STAMP_WIDTH,
+    };
+    let y0 = match corner {
+        StampCorner::TopLeft | StampCorner::TopRight => top - STAMP_MARGIN - STAMP_HEIGHT,
+        StampCorner::BottomLeft | StampCorner::BottomRight => bottom + STAMP_MARGIN,
+    };
+    [x0, y0, x0 + STAMP_WIDTH, y0 + STAMP_HEIGHT]
+}
+
+fn overlaps(span: &TextSpan, region: [f32; 4]) -> bool {
+    let [x0, y0, x1, y1] = region;
+    span.x < x1 && span.x + span.width > x0 && span.y < y1 && span.y + span.height > y0
+}
+
+/// 1-based numbers of pages that already have text where a stamp in
+/// `corner` would go. Pages whose content can't be read are skipped.
+pub fn detect_stamp_collision(file_path: &str, corner: StampCorner) -> Result<Vec<usize>, String> {
+    let doc = Document::load(file_path).map_err(|e| format!("Failed to load PDF: {}", e))?;
+
+    let mut collisions = Vec::new();
+    for (page_number, page_id) in doc.get_pages() {
+        let media_box = page_media_box(&doc, page_id).unwrap_or([0.0, 0.0, 595.0, 842.0]);
+        let region = stamp_region(media_box, corner);
+        let Ok(spans) = page_text_spans(&doc, page_id) else {
+            continue;
+        };
+        if spans.iter().any(|span| overlaps(span, region)) {
+            collisions.push(page_number as usize);
+        }
+    }
+    Ok(collisions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::fixtures::{build_pdf, temp_pdf_path};
+    use lopdf::Dictionary;
+
+    #[test]
+    fn test_top_right_reference_collides_with_top_right_stamp() {
+        let path = temp_pdf_path("collision");
+        let letterhead = b"BT /F1 10 Tf 460 800 Td (Our ref: TAN/2024/001) Tj ET".to_vec();
+        let body = b"BT /F1 12 Tf 72 700 Td (Dear Sirs) Tj ET".to_vec();
+        build_pdf(vec![
+            (letterhead, Dictionary::new()),
+            (body, Dictionary::new()),
+        ])
+        .save(&path)
+        .unwrap();
+
+        let top_right = detect_stamp_collision(path.to_str().unwrap(), StampCorner::TopRight);
+        let bottom_right = detect_stamp_collision(path.to_str().unwrap(), StampCorner::BottomRight);
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(top_right.unwrap(), vec![1]);
+        assert!(bottom_right.unwrap().is_empty());
+    }
+}
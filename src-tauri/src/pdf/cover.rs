@@ -0,0 +1,150 @@
+//! Cover page generation for bundles
+//!
+//! A single title page with the bundle title, case name and parties
+//! centred on the page, placed before everything else in the bundle.
+
+use lopdf::{Document, Object};
+use serde::{Deserialize, Serialize};
+
+use super::endorsement::{
+    empty_document, encode_pdf_text, insert_text_page, media_box_size, A4_MEDIA_BOX,
+};
+
+/// Rough Helvetica advance per character, as a fraction of the font size
+const CHAR_WIDTH: f32 = 0.5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverInfo {
+    pub title: String,
+    pub case_name: String,
+    #[serde(default)]
+    pub parties: Vec<String>,
+    pub date: String,
+}
+
+/// One line of text horizontally centred at height `y`
+fn centred_line(text: &str, size: f32, y: f32, width: f32) -> Result<String, String> {
+    let text_width = text.chars().count() as f32 * size * CHAR_WIDTH;
+    let x = ((width - text_width) / 2.0).max(36.0);
+    Ok(format!(
+        "BT /F1 {} Tf {} {} Td ({}) Tj ET\n",
+        size,
+        x,
+        y,
+        encode_pdf_text(text)?
+    ))
+}
+
+fn cover_content(info: &CoverInfo, width: f32, height: f32) -> Result<Vec<u8>, String> {
+    let mut content = centred_line(&info.title.to_uppercase(), 20.0, height * 0.7, width)?;
+    let mut y = height * 0.7 - 48.0;
+    content.push_str(&centred_line(&info.case_name, 14.0, y, width)?);
+
+    y -= 48.0;
+    for (i, party) in info.parties.iter().enumerate() {
+        if i > 0 {
+            content.push_str(&centred_line("and", 11.0, y, width)?);
+            y -= 24.0;
+        }
+        content.push_str(&centred_line(party, 12.0, y, width)?);
+        y -= 24.0;
+    }
+
+    content.push_str(&centred_line(&info.date, 11.0, 120.0, width)?);
+    Ok(content.into_bytes())
+}
+
+/// Write a standalone single-page A4 cover PDF
+pub fn generate_cover_pdf(info: &CoverInfo, output_path: &str) -> Result<(), String> {
+    let (mut doc, pages_id) = empty_document();
+    let media_box: Vec<Object> = A4_MEDIA_BOX.iter().map(|&v| v.into()).collect();
+    let (width, height) = media_box_size(&media_box);
+    insert_text_page(
+        &mut doc,
+        pages_id,
+        false,
+        media_box,
+        cover_content(info, width, height)?,
+    )?;
+
+    doc.save(output_path)
+        .map_err(|e| format!("Failed to save cover page: {}", e))?;
+    Ok(())
+}
+
+/// Add a cover page to the front of a compiled bundle.
+/// Returns the bundle's page count including the cover.
+pub fn prepend_cover_page(
+    bundle_path: &str,
+    info: &CoverInfo,
+    output_path: &str,
+) -> Result<usize, String> {
+    let mut doc =
+        Document::load(bundle_path).map_err(|e| format!("Failed to load bundle: {}", e))?;
+    let pages_id = doc
+        .catalog()
+        .and_then(|catalog| catalog.get(b"Pages"))
+        .and_then(Object::as_reference)
+        .map_err(|e| format!("Failed to find page tree: {}", e))?;
+
+    let media_box: Vec<Object> = A4_MEDIA_BOX.iter().map(|&v| v.into()).collect();
+    let (width, height) = media_box_size(&media_box);
+    let content = cover_content(info, width, height)?;
+    insert_text_page(&mut doc, pages_id, true, media_box, content)?;
+
+    let total_pages = doc.get_pages().len();
+    doc.save(output_path)
+        .map_err(|e| format!("Failed to save bundle: {}", e))?;
+    Ok(total_pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::fixtures::{temp_pdf_path, write_text_pdf};
+    use crate::pdf::text::extract_page_text;
+
+    fn info() -> CoverInfo {
+        CoverInfo {
+            title: "Bundle of Documents".to_string(),
+            case_name: "HC/S 123/2026".to_string(),
+            parties: vec!["Tan Ah Kow".to_string(), "Lim Holdings Pte Ltd".to_string()],
+            date: "16 October 2026".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_cover_is_one_page() {
+        let output = temp_pdf_path("cover");
+        generate_cover_pdf(&info(), output.to_str().unwrap()).unwrap();
+
+        let doc = Document::load(&output).unwrap();
+        let pages = doc.get_pages();
+        let text = extract_page_text(&doc, pages[&1]).unwrap();
+        std::fs::remove_file(&output).ok();
+
+        assert_eq!(pages.len(), 1);
+        assert!(text.starts_with("BUNDLE OF DOCUMENTS"));
+        assert!(text.contains("Tan Ah Kow\nand\nLim Holdings Pte Ltd"));
+    }
+
+    #[test]
+    fn test_cover_adds_a_front_page() {
+        let bundle = temp_pdf_path("cover-bundle");
+        let output = temp_pdf_path("cover-out");
+        write_text_pdf(&bundle, &["Tab 1", "Tab 2"]);
+
+        let total = prepend_cover_page(bundle.to_str().unwrap(), &info(), output.to_str().unwrap())
+            .unwrap();
+        let doc = Document::load(&output).unwrap();
+        let pages = doc.get_pages();
+        let first = extract_page_text(&doc, pages[&1]).unwrap();
+        let second = extract_page_text(&doc, pages[&2]).unwrap();
+        std::fs::remove_file(&bundle).ok();
+        std::fs::remove_file(&output).ok();
+
+        assert_eq!(total, 3);
+        assert!(first.contains("HC/S 123/2026"));
+        assert!(second.contains("Tab 1"));
+    }
+}
@@ -0,0 +1,331 @@
+//! Endorsement page generation
+//!
+//! The endorsement is the closing page of a filed bundle, stating how many
+//! pages the bundle contains and who prepared it.
+
+use lopdf::{dictionary, Document, Object, ObjectId, Stream};
+use serde::{Deserialize, Serialize};
+
+/// A4 in points, used when there is no bundle page to match
+pub(super) const A4_MEDIA_BOX: [i64; 4] = [0, 0, 595, 842];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndorsementInfo {
+    pub preparer: String,
+    pub firm: String,
+    pub date: String,
+}
+
+/// WinAnsi codes 0x80-0x9F, which differ from Latin-1
+const WIN_ANSI_HIGH: [(char, u8); 27] = [
+    ('€', 0x80),
+    ('‚', 0x82),
+    ('ƒ', 0x83),
+    ('„', 0x84),
+    ('…', 0x85),
+    ('†', 0x86),
+    ('‡', 0x87),
+    ('ˆ', 0x88),
+    ('‰', 0x89),
+    ('Š', 0x8A),
+    ('‹', 0x8B),
+    ('Œ', 0x8C),
+    ('Ž', 0x8E),
+    ('\u{2018}', 0x91),
+    ('\u{2019}', 0x92),
+    ('\u{201C}', 0x93),
+    ('\u{201D}', 0x94),
+    ('•', 0x95),
+    ('–', 0x96),
+    ('—', 0x97),
+    ('˜', 0x98),
+    ('™', 0x99),
+    ('š', 0x9A),
+    ('›', 0x9B),
+    ('œ', 0x9C),
+    ('ž', 0x9E),
+    ('Ÿ', 0x9F),
+];
+
+/// The WinAnsi byte for `ch`, if the standard fonts can draw it
+fn win_ansi_byte(ch: char) -> Option<u8> {
+    match ch {
+        ' '..='~' | '\u{A0}'..='\u{FF}' => Some(ch as u8),
+        _ => WIN_ANSI_HIGH
+            .iter()
+            .find(|(c, _)| *c == ch)
+            .map(|(_, byte)| *byte),
+    }
+}
+
+/// Encode text as the body of a PDF literal string for a WinAnsi Helvetica
+/// font. `(`, `)` and `\` are escaped and non-ASCII characters written as
+/// octal escapes. Characters the font can't draw are an error rather than
+/// mojibake on the page.
+pub(super) fn encode_pdf_text(text: &str) -> Result<String, String> {
+    let mut encoded = String::with_capacity(text.len());
+    for ch in text.chars() {
+        let ch = if ch.is_whitespace() && !ch.is_ascii_graphic() {
+            ' '
+        } else {
+            ch
+        };
+        match win_ansi_byte(ch) {
+            Some(b'(' | b')' | b'\\') => {
+                encoded.push('\\');
+                encoded.push(ch);
+            }
+            Some(byte) if byte.is_ascii() => encoded.push(ch),
+            Some(byte) => encoded.push_str(&format!("\\{:03o}", byte)),
+            None => {
+                return Err(format!(
+                    "Cannot print \"{}\": '{}' is not supported by the PDF font",
+                    text, ch
+                ))
+            }
+        }
+    }
+    Ok(encoded)
+}
+
+/// Helvetica with WinAnsi encoding, matching [`encode_pdf_text`]
+pub(super) fn helvetica() -> lopdf::Dictionary {
+    dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+        "Encoding" => "WinAnsiEncoding",
+    }
+}
+
+/// Content stream for the endorsement text, optionally with a page number footer
+fn endorsement_content(
+    info: &EndorsementInfo,
+    total_pages: usize,
+    page_number: Option<usize>,
+    width: f32,
+    height: f32,
+) -> Result<Vec<u8>, String> {
+    let lines = [
+        format!("This bundle contains {} pages.", total_pages),
+        format!("Prepared by: {}", info.preparer),
+        format!("Firm: {}", info.firm),
+        format!("Date: {}", info.date),
+    ];
+
+    let mut content = format!("BT /F1 12 Tf 18 TL 72 {} Td\n", height - 144.0);
+    for line in &lines {
+        content.push_str(&format!("({}) Tj T*\n", encode_pdf_text(line)?));
+    }
+    content.push_str("ET\n");
+
+    if let Some(page_number) = page_number {
+        content.push_str(&format!(
+            "BT /F1 10 Tf {} 36 Td ({}) Tj ET\n",
+            width / 2.0 - 10.0,
+            page_number
+        ));
+    }
+    Ok(content.into_bytes())
+}
+
+/// Add a Helvetica text page to the start or end of `doc`'s page tree
+pub(super) fn insert_text_page(
+    doc: &mut Document,
+    pages_id: ObjectId,
+    at_front: bool,
+    media_box: Vec<Object>,
+    content: Vec<u8>,
+) -> Result<(), String> {
+    let font_id = doc.add_object(helvetica());
+    let content_id = doc.add_object(Stream::new(dictionary! {}, content));
+    let page_id = doc.add_object(dictionary! {
+        "Type" => "Page",
+        "Parent" => pages_id,
+        "MediaBox" => media_box,
+        "Contents" => content_id,
+        "Resources" => dictionary! { "Font" => dictionary! { "F1" => font_id } },
+    });
+
+    let pages = doc
+        .get_dictionary_mut(pages_id)
+        .map_err(|e| format!("Failed to read page tree: {}", e))?;
+    let count = pages.get(b"Count").and_then(Object::as_i64).unwrap_or(0);
+    pages.set("Count", count + 1);
+    match pages.get_mut(b"Kids") {
+        Ok(Object::Array(kids)) if at_front => kids.insert(0, page_id.into()),
+        Ok(Object::Array(kids)) => kids.push(page_id.into()),
+        _ => pages.set("Kids", vec![page_id.into()]),
+    }
+    Ok(())
+}
+
+pub(super) fn media_box_size(media_box: &[Object]) -> (f32, f32) {
+    let value = |i: usize| {
+        media_box
+            .get(i)
+            .and_then(|o| o.as_float().ok())
+            .unwrap_or(0.0)
+    };
+    (value(2) - value(0), value(3) - value(1))
+}
+
+/// A new document with an empty page tree, returning the tree's id
+pub(super) fn empty_document() -> (Document, ObjectId) {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.add_object(dictionary! {
+        "Type" => "Pages",
+        "Kids" => Vec::<Object>::new(),
+        "Count" => 0,
+    });
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+    (doc, pages_id)
+}
+
+/// Write a standalone single-page endorsement PDF
+pub fn generate_endorsement_page(
+    info: &EndorsementInfo,
+    total_pages: usize,
+    output_path: &str,
+) -> Result<(), String> {
+    let (mut doc, pages_id) = empty_document();
+    let media_box: Vec<Object> = A4_MEDIA_BOX.iter().map(|&v| v.into()).collect();
+    let (width, height) = media_box_size(&media_box);
+    let content = endorsement_content(info, total_pages, None, width, height)?;
+    insert_text_page(&mut doc, pages_id, false, media_box, content)?;
+
+    doc.save(output_path)
+        .map_err(|e| format!("Failed to save endorsement page: {}", e))?;
+    Ok(())
+}
+
+/// Append an endorsement page to a compiled bundle.
+///
+/// When `numbered`, the endorsement counts towards the total and shows its
+/// own page number; otherwise it reports only the bundle's pages.
+/// Returns the page total printed on the endorsement.
+pub fn append_endorsement_page(
+    bundle_path: &str,
+    info: &EndorsementInfo,
+    numbered: bool,
+    output_path: &str,
+) -> Result<usize, String> {
+    let mut doc =
+        Document::load(bundle_path).map_err(|e| format!("Failed to load bundle: {}", e))?;
+
+    let pages = doc.get_pages();
+    let bundle_pages = pages.len();
+    let pages_id = doc
+        .catalog()
+        .and_then(|catalog| catalog.get(b"Pages"))
+        .and_then(Object::as_reference)
+        .map_err(|e| format!("Failed to find page tree: {}", e))?;
+
+    // Match the size of the bundle's last page
+    let media_box = pages
+        .values()
+        .last()
+        .and_then(|page_id| doc.get_dictionary(*page_id).ok())
+        .and_then(|page| page.get(b"MediaBox").ok())
+        .and_then(|media_box| media_box.as_array().ok())
+        .cloned()
+        .unwrap_or_else(|| A4_MEDIA_BOX.iter().map(|&v| v.into()).collect());
+    let (width, height) = media_box_size(&media_box);
+
+    let (total_pages, page_number) = if numbered {
+        (bundle_pages + 1, Some(bundle_pages + 1))
+    } else {
+        (bundle_pages, None)
+    };
+    let content = endorsement_content(info, total_pages, page_number, width, height)?;
+    insert_text_page(&mut doc, pages_id, false, media_box, content)?;
+
+    doc.save(output_path)
+        .map_err(|e| format!("Failed to save bundle: {}", e))?;
+    Ok(total_pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::fixtures::{temp_pdf_path, write_text_pdf};
+    use crate::pdf::text::extract_page_text;
+
+    fn info() -> EndorsementInfo {
+        EndorsementInfo {
+            preparer: "Tan Ah Kow".to_string(),
+            firm: "Lee (Singapore) LLP".to_string(),
+            date: "16 October 2026".to_string(),
+        }
+    }
+
+    fn last_page_text(path: &std::path::Path) -> (usize, String) {
+        let doc = Document::load(path).unwrap();
+        let pages = doc.get_pages();
+        let last = *pages.values().last().unwrap();
+        (pages.len(), extract_page_text(&doc, last).unwrap())
+    }
+
+    #[test]
+    fn test_numbered_endorsement_counts_itself() {
+        let bundle = temp_pdf_path("endorse-bundle");
+        let output = temp_pdf_path("endorse-out");
+        write_text_pdf(&bundle, &["Tab 1", "Tab 2", "Tab 3"]);
+
+        let total = append_endorsement_page(
+            bundle.to_str().unwrap(),
+            &info(),
+            true,
+            output.to_str().unwrap(),
+        )
+        .unwrap();
+        let (page_count, text) = last_page_text(&output);
+        std::fs::remove_file(&bundle).ok();
+        std::fs::remove_file(&output).ok();
+
+        assert_eq!(total, 4);
+        assert_eq!(page_count, 4);
+        assert!(text.contains("This bundle contains 4 pages."));
+        assert!(text.contains("Firm: Lee (Singapore) LLP"));
+        assert!(text.ends_with('4'));
+    }
+
+    #[test]
+    fn test_unnumbered_endorsement_reports_bundle_pages() {
+        let bundle = temp_pdf_path("endorse-bundle");
+        let output = temp_pdf_path("endorse-out");
+        write_text_pdf(&bundle, &["Tab 1", "Tab 2", "Tab 3"]);
+
+        let total = append_endorsement_page(
+            bundle.to_str().unwrap(),
+            &info(),
+            false,
+            output.to_str().unwrap(),
+        )
+        .unwrap();
+        let (page_count, text) = last_page_text(&output);
+        std::fs::remove_file(&bundle).ok();
+        std::fs::remove_file(&output).ok();
+
+        assert_eq!(total, 3);
+        assert_eq!(page_count, 4);
+        assert!(text.contains("This bundle contains 3 pages."));
+    }
+
+    #[test]
+    fn test_standalone_endorsement_page() {
+        let output = temp_pdf_path("endorse-standalone");
+        generate_endorsement_page(&info(), 120, output.to_str().unwrap()).unwrap();
+
+        let (page_count, text) = last_page_text(&output);
+        std::fs::remove_file(&output).ok();
+
+        assert_eq!(page_count, 1);
+        assert!(text.contains("This bundle contains 120 pages."));
+        assert!(text.contains("Prepared by: Tan Ah Kow"));
+    }
+}
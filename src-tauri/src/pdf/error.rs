@@ -0,0 +1,57 @@
+//! PDF error type
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// Errors from loading PDFs, sent to the frontend as `{ kind, message }`
+#[derive(Debug)]
+pub enum PdfError {
+    /// No file at the given path
+    NotFound(String),
+    /// The PDF needs a password to open
+    Encrypted,
+    /// A password was supplied but doesn't open the PDF
+    IncorrectPassword,
+    /// The file can't be read or parsed as a PDF
+    Invalid(String),
+    /// This build lacks what the operation needs, e.g. OCR or PDFium
+    Unavailable(String),
+}
+
+impl PdfError {
+    /// Stable identifier for the frontend to branch on
+    pub fn kind(&self) -> &'static str {
+        match self {
+            PdfError::NotFound(_) => "not_found",
+            PdfError::Encrypted => "encrypted",
+            PdfError::IncorrectPassword => "incorrect_password",
+            PdfError::Invalid(_) => "invalid",
+            PdfError::Unavailable(_) => "unavailable",
+        }
+    }
+}
+
+impl fmt::Display for PdfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PdfError::NotFound(path) => write!(f, "File not found: {}", path),
+            PdfError::Encrypted => write!(f, "PDF is encrypted and needs a password"),
+            PdfError::IncorrectPassword => write!(f, "Incorrect password for encrypted PDF"),
+            PdfError::Invalid(message) | PdfError::Unavailable(message) => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PdfError {}
+
+impl Serialize for PdfError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("PdfError", 2)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
@@ -0,0 +1,90 @@
+//! Exhibit stamps for affidavit exhibits
+//!
+//! Each exhibit's first page carries a boxed statement tying it to the
+//! affidavit: "This is the exhibit marked "A" referred to in the affidavit
+//! of ...". Later pages are left untouched.
+
+use lopdf::Document;
+
+use super::certificate::wrap_words;
+use super::collision::StampCorner;
+use super::stamp::stamp_page_box;
+
+/// Characters per line before the stamp statement wraps
+const EXHIBIT_LINE_CHARS: usize = 48;
+
+/// The stamp statement, where `affidavit_text` continues the sentence,
+/// e.g. "the affidavit of Tan Ah Kow affirmed on 16 October 2026"
+pub fn exhibit_stamp_text(exhibit_label: &str, affidavit_text: &str) -> String {
+    format!(
+        "This is the exhibit marked \"{}\" referred to in {}",
+        exhibit_label,
+        affidavit_text.trim()
+    )
+}
+
+/// Overlay the exhibit stamp on the first page of `input_path`
+pub fn inject_exhibit_stamp(
+    input_path: &str,
+    output_path: &str,
+    exhibit_label: &str,
+    affidavit_text: &str,
+    corner: StampCorner,
+) -> Result<(), String> {
+    let mut doc = Document::load(input_path).map_err(|e| format!("Failed to load PDF: {}", e))?;
+    let first_page = doc
+        .page_iter()
+        .next()
+        .ok_or_else(|| "PDF has no pages".to_string())?;
+
+    let text = exhibit_stamp_text(exhibit_label, affidavit_text);
+    stamp_page_box(
+        &mut doc,
+        first_page,
+        corner,
+        &wrap_words(&text, EXHIBIT_LINE_CHARS),
+    )?;
+
+    doc.save(output_path)
+        .map_err(|e| format!("Failed to save PDF: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::fixtures::{temp_pdf_path, write_text_pdf};
+
+    fn page_contents(path: &std::path::Path) -> Vec<String> {
+        let doc = Document::load(path).unwrap();
+        doc.page_iter()
+            .map(|page_id| {
+                String::from_utf8_lossy(&doc.get_page_content(page_id).unwrap()).into_owned()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_only_first_page_is_stamped() {
+        let input = temp_pdf_path("exhibit-in");
+        let output = temp_pdf_path("exhibit-out");
+        write_text_pdf(&input, &["Invoice", "Terms", "Signature"]);
+
+        inject_exhibit_stamp(
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+            "TAK-1",
+            "the affidavit of Tan Ah Kow affirmed on 16 October 2026",
+            StampCorner::BottomRight,
+        )
+        .unwrap();
+        let before = page_contents(&input);
+        let after = page_contents(&output);
+        std::fs::remove_file(&input).ok();
+        std::fs::remove_file(&output).ok();
+
+        assert!(after[0].contains("(This is the exhibit marked \"TAK-1\" referred to) Tj"));
+        assert!(after[0].contains(" re S"));
+        assert_eq!(after[1..], before[1..]);
+    }
+}
@@ -0,0 +1,204 @@
+//! Synthetic PDF builders for tests
+
+use lopdf::{dictionary, Dictionary, Document, Object, Stream};
+use std::path::PathBuf;
+
+/// A unique path in the system temp directory for a generated test PDF
+pub fn temp_pdf_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("casepilot-{}-{}.pdf", name, uuid::Uuid::new_v4()))
+}
+
+/// Build a document whose pages use the given raw content streams and resources
+pub fn build_pdf(pages: Vec<(Vec<u8>, Dictionary)>) -> Document {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+
+    let mut kids = Vec::new();
+    for (content, resources) in pages {
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+            "Contents" => content_id,
+            "Resources" => resources,
+        });
+        kids.push(Object::Reference(page_id));
+    }
+
+    let count = kids.len() as i64;
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => count,
+        }),
+    );
+
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+    doc
+}
+
+/// Write a PDF with one page of Helvetica text per entry in `pages`
+pub fn write_text_pdf(path: &PathBuf, pages: &[&str]) {
+    let pages = pages
+        .iter()
+        .map(|text| {
+            let mut content = String::from("BT /F1 12 Tf 72 770 Td 14 TL\n");
+            for line in text.lines() {
+                content.push_str(&format!("({}) Tj T*\n", line));
+            }
+            content.push_str("ET");
+            let resources = dictionary! {
+                "Font" => dictionary! {
+                    "F1" => dictionary! {
+                        "Type" => "Font",
+                        "Subtype" => "Type1",
+                        "BaseFont" => "Helvetica",
+                    },
+                },
+            };
+            (content.into_bytes(), resources)
+        })
+        .collect();
+
+    build_pdf(pages)
+        .save(path)
+        .expect("Failed to write text PDF");
+}
+
+/// Write a single-page PDF that only paints an image (like a scan)
+pub fn write_image_only_pdf(path: &PathBuf) {
+    let mut doc = build_pdf(vec![(
+        b"q 595 0 0 842 0 0 cm /Im1 Do Q".to_vec(),
+        Dictionary::new(),
+    )]);
+
+    let image_id = doc.add_object(Stream::new(
+        dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Image",
+            "Width" => 2,
+            "Height" => 2,
+            "ColorSpace" => "DeviceGray",
+            "BitsPerComponent" => 8,
+        },
+        vec![0, 255, 255, 0],
+    ));
+
+    let page_id = doc.page_iter().next().expect("fixture has a page");
+    let page = doc
+        .get_object_mut(page_id)
+        .and_then(Object::as_dict_mut)
+        .expect("page dictionary");
+    page.set(
+        "Resources",
+        dictionary! { "XObject" => dictionary! { "Im1" => image_id } },
+    );
+
+    doc.save(path).expect("Failed to write image-only PDF");
+}
+
+/// Write a one-page "scan": a Flate-compressed 32x32 grayscale image
+/// whose pixels come from `pixel(x, y)`
+pub fn write_scan_pdf(path: &PathBuf, pixel: impl Fn(u32, u32) -> u8) {
+    let mut samples = Vec::with_capacity(32 * 32);
+    for y in 0..32 {
+        for x in 0..32 {
+            samples.push(pixel(x, y));
+        }
+    }
+
+    let mut doc = build_pdf(vec![(
+        b"q 595 0 0 842 0 0 cm /Im1 Do Q".to_vec(),
+        Dictionary::new(),
+    )]);
+    let mut image = Stream::new(
+        dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Image",
+            "Width" => 32,
+            "Height" => 32,
+            "ColorSpace" => "DeviceGray",
+            "BitsPerComponent" => 8,
+        },
+        samples,
+    );
+    image.compress().expect("Failed to compress scan");
+    let image_id = doc.add_object(image);
+
+    let page_id = doc.page_iter().next().expect("fixture has a page");
+    doc.get_dictionary_mut(page_id)
+        .expect("page dictionary")
+        .set(
+            "Resources",
+            dictionary! { "XObject" => dictionary! { "Im1" => image_id } },
+        );
+
+    doc.save(path).expect("Failed to write scan PDF");
+}
+
+/// RC4 keystream applied to `data` (encryption and decryption are the same)
+fn rc4(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut state: Vec<u8> = (0..=255).collect();
+    let mut j = 0u8;
+    for i in 0..256 {
+        j = j.wrapping_add(state[i]).wrapping_add(key[i % key.len()]);
+        state.swap(i, j as usize);
+    }
+    let (mut i, mut j) = (0u8, 0u8);
+    data.iter()
+        .map(|byte| {
+            i = i.wrapping_add(1);
+            j = j.wrapping_add(state[i as usize]);
+            state.swap(i as usize, j as usize);
+            let k = state[state[i as usize].wrapping_add(state[j as usize]) as usize];
+            byte ^ k
+        })
+        .collect()
+}
+
+/// Write a two-page PDF with a Standard security handler (RC4 40-bit, R2)
+/// that opens with `user_password`. Page content isn't actually encrypted;
+/// only the password check matters to the loader.
+pub fn write_encrypted_pdf(path: &PathBuf, user_password: &str) {
+    const PAD: [u8; 32] = [
+        0x28, 0xBF, 0x4E, 0x5E, 0x4E, 0x75, 0x8A, 0x41, 0x64, 0x00, 0x4E, 0x56, 0xFF, 0xFA, 0x01,
+        0x08, 0x2E, 0x2E, 0x00, 0xB6, 0xD0, 0x68, 0x3E, 0x80, 0x2F, 0x0C, 0xA9, 0xFE, 0x64, 0x53,
+        0x69, 0x7A,
+    ];
+
+    let mut doc = build_pdf(vec![
+        (Vec::new(), Dictionary::new()),
+        (Vec::new(), Dictionary::new()),
+    ]);
+    let encrypt_id = doc.add_object(dictionary! {
+        "Filter" => "Standard",
+        "V" => 1,
+        "R" => 2,
+        "O" => Object::string_literal(vec![0x4Fu8; 32]),
+        "U" => Object::string_literal(vec![0u8; 32]),
+        "P" => -4,
+    });
+    doc.trailer.set("Encrypt", encrypt_id);
+    doc.trailer.set(
+        "ID",
+        vec![
+            Object::string_literal(b"casepilot-fixture".to_vec()),
+            Object::string_literal(b"casepilot-fixture".to_vec()),
+        ],
+    );
+
+    let key = lopdf::encryption::get_encryption_key(&doc, user_password, false)
+        .expect("Failed to derive fixture key");
+    doc.get_dictionary_mut(encrypt_id)
+        .expect("encrypt dictionary")
+        .set("U", Object::string_literal(rc4(&key, &PAD)));
+
+    doc.save(path).expect("Failed to write encrypted PDF");
+}
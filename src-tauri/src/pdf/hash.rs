@@ -0,0 +1,62 @@
+//! Hashes recorded for imported files
+//!
+//! Hashing reads the whole file and decodes the first page's scan, so callers
+//! run it on the blocking pool under the PDF limiter and hand the result to
+//! the database layer.
+
+use sha2::{Digest, Sha256};
+
+/// Hashes stored on a file record; `None` when the file can't be read
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileHashes {
+    /// SHA-256 of the file bytes, hex encoded
+    pub content_hash: Option<String>,
+    /// Perceptual hash of the first page's scan, hex encoded
+    pub visual_hash: Option<String>,
+}
+
+/// Hex SHA-256 of a file's bytes, or `None` if it can't be read
+pub fn file_sha256(path: &str) -> Option<String> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).ok()?;
+    Some(format!("{:x}", hasher.finalize()))
+}
+
+/// Hex perceptual hash of a file's first page, or `None` if it has no scan to hash
+pub fn first_page_phash(path: &str) -> Option<String> {
+    super::page_phash(path, 0)
+        .ok()
+        .map(|hash| format!("{:016x}", hash))
+}
+
+/// Every hash stored for the file at `path`
+pub fn file_hashes(path: &str) -> FileHashes {
+    FileHashes {
+        content_hash: file_sha256(path),
+        visual_hash: first_page_phash(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_bytes_hash_alike() {
+        let dir = std::env::temp_dir().join(format!("casepilot-hash-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let paths = ["first.pdf", "copy.pdf", "other.pdf"].map(|name| dir.join(name));
+        std::fs::write(&paths[0], b"%PDF-1.4 same bytes").unwrap();
+        std::fs::write(&paths[1], b"%PDF-1.4 same bytes").unwrap();
+        std::fs::write(&paths[2], b"%PDF-1.4 different bytes").unwrap();
+
+        let hashes = paths.map(|path| file_sha256(path.to_str().unwrap()).unwrap());
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(hashes[0].len(), 64);
+        assert_eq!(hashes[0], hashes[1]);
+        assert_ne!(hashes[0], hashes[2]);
+        assert_eq!(file_sha256("/no/such/file.pdf"), None);
+    }
+}
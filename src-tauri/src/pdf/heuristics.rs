@@ -1,8 +1,16 @@
 //! Document heuristics: type detection, date parsing, auto-description
 
+use chrono::NaiveDate;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::sync::LazyLock;
 
-use super::text::extract_first_page_text;
+use super::error::PdfError;
+use super::load::load_document;
+use super::ocr::ocr_page_text;
+use super::scanned::{has_little_text, is_scanned_pdf};
+use super::text::first_page_text;
 
 /// Extracted metadata from email-style PDFs
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -12,30 +20,228 @@ pub struct ExtractedDocumentInfo {
     pub recipient: Option<String>,
     pub subject: Option<String>,
     pub document_type: Option<String>,
+    /// 0-1: how strongly the page's phrases support `document_type`
+    pub document_type_confidence: Option<f32>,
+    /// Court case number such as "HC/OC 123/2024"
+    pub suit_number: Option<String>,
+    /// Parties named in the cause title ("Between ... Plaintiff And ... Defendant")
+    pub plaintiff: Option<String>,
+    pub defendant: Option<String>,
     pub first_page_text: Option<String>,
+    /// Every date found on the first page, in order, for the user to pick from
+    pub dates: Vec<String>,
+    /// No extractable text on the first page (likely a scan)
+    pub needs_ocr: bool,
+    /// Most pages are images with little or no text; the UI suggests OCR
+    pub scanned: bool,
 }
 
-/// Try to extract structured information from the first page of a PDF
-pub fn extract_document_info(file_path: &str) -> Result<ExtractedDocumentInfo, String> {
-    let first_page = extract_first_page_text(file_path, 2000)?;
+/// A document type and the keywords that identify it. Rules are tried from
+/// the highest priority down and the first match wins.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypeRule {
+    pub document_type: String,
+    /// Matched case-insensitively against the first page
+    pub keywords: Vec<String>,
+    pub priority: i32,
+    /// Every keyword must appear, rather than any one of them
+    #[serde(default)]
+    pub match_all: bool,
+}
+
+/// Phrases that identify a built-in document type
+struct TypeKeywords {
+    document_type: &'static str,
+    phrases: &'static [&'static str],
+    /// Every phrase must appear, rather than any one of them
+    match_all: bool,
+}
+
+const fn any_of(document_type: &'static str, phrases: &'static [&'static str]) -> TypeKeywords {
+    TypeKeywords {
+        document_type,
+        phrases,
+        match_all: false,
+    }
+}
+
+/// Non-empty lines at the top of a page treated as its title
+const TITLE_LINES: usize = 3;
+
+/// Evidence weight of a matched phrase in the title, and elsewhere on the page
+const TITLE_WEIGHT: f32 = 0.7;
+const BODY_WEIGHT: f32 = 0.3;
+
+/// Built-in document types in priority order, so titles that name a
+/// specific court document come before words any document may use
+const DOCUMENT_TYPES: &[TypeKeywords] = &[
+    any_of(
+        "Bundle of Documents",
+        &["bundle of documents", "agreed bundle"],
+    ),
+    any_of(
+        "Skeletal Submissions",
+        &["skeletal submissions", "skeletal arguments"],
+    ),
+    any_of("Notice of Appeal", &["notice of appeal"]),
+    any_of(
+        "Order of Court",
+        &["order of court", "it is ordered", "it is hereby ordered"],
+    ),
+    any_of("Writ of Summons", &["writ of summons"]),
+    any_of(
+        "Originating Application",
+        &["originating application", "originating summons"],
+    ),
+    any_of("Statement of Claim", &["statement of claim"]),
+    any_of("Affidavit", &["affidavit"]),
+    any_of("Defence", &["defence"]),
+    any_of("Exhibit", &["exhibit"]),
+    any_of("Contract", &["contract", "agreement"]),
+    any_of("Invoice", &["invoice"]),
+    TypeKeywords {
+        document_type: "Email",
+        phrases: &["from:", "to:"],
+        match_all: true,
+    },
+    any_of("Letter", &["letter", "dear"]),
+];
+
+/// The built-in rules. Priorities are spaced 10 apart so custom rules can
+/// slot in between.
+pub fn default_type_rules() -> Vec<TypeRule> {
+    DOCUMENT_TYPES
+        .iter()
+        .enumerate()
+        .map(|(i, rule)| TypeRule {
+            document_type: rule.document_type.to_string(),
+            keywords: rule.phrases.iter().map(|p| p.to_string()).collect(),
+            priority: ((DOCUMENT_TYPES.len() - i) * 10) as i32,
+            match_all: rule.match_all,
+        })
+        .collect()
+}
+
+/// The built-in rules with `custom` added. A custom rule replaces the
+/// built-in rule for the same document type.
+pub fn merge_type_rules(custom: Vec<TypeRule>) -> Vec<TypeRule> {
+    let mut rules: Vec<TypeRule> = default_type_rules()
+        .into_iter()
+        .filter(|rule| {
+            !custom
+                .iter()
+                .any(|c| c.document_type.eq_ignore_ascii_case(&rule.document_type))
+        })
+        .collect();
+    rules.extend(custom);
+    rules
+}
+
+/// The highest-priority document type whose keywords appear in `text_lower`,
+/// with a confidence that grows with each matched keyword, weighted by
+/// whether it appears in the title
+fn detect_document_type(text_lower: &str, rules: &[TypeRule]) -> Option<(String, f32)> {
+    // Titles often break across lines
+    let collapse = |lines: &[&str]| {
+        lines
+            .join(" ")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let lines: Vec<&str> = text_lower
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .collect();
+    let title = collapse(&lines[..lines.len().min(TITLE_LINES)]);
+    let text = collapse(&lines);
+
+    let mut ordered: Vec<&TypeRule> = rules.iter().collect();
+    ordered.sort_by_key(|rule| std::cmp::Reverse(rule.priority));
+
+    for rule in ordered {
+        let keywords: Vec<String> = rule
+            .keywords
+            .iter()
+            .map(|k| collapse(&[&k.to_lowercase()]))
+            .filter(|k| !k.is_empty())
+            .collect();
+        let mut present = keywords.iter().map(|k| text.contains(k.as_str()));
+        let matched = if rule.match_all {
+            !keywords.is_empty() && present.all(|p| p)
+        } else {
+            present.any(|p| p)
+        };
+        if !matched {
+            continue;
+        }
+
+        let doubt: f32 = keywords
+            .iter()
+            .map(|keyword| {
+                if title.contains(keyword.as_str()) {
+                    1.0 - TITLE_WEIGHT
+                } else if text.contains(keyword.as_str()) {
+                    1.0 - BODY_WEIGHT
+                } else {
+                    1.0
+                }
+            })
+            .product();
+        return Some((rule.document_type.clone(), 1.0 - doubt));
+    }
+    None
+}
+
+/// `text` from a page's text layer, or the page read by `ocr` when the layer
+/// is too thin to be real text (a scan, perhaps with a stamp). OCR only runs
+/// in that case, and if it fails the text layer is kept.
+fn with_ocr_fallback(text: String, ocr: impl FnOnce() -> Result<String, PdfError>) -> String {
+    if !has_little_text(&text) {
+        return text;
+    }
+    match ocr() {
+        Ok(ocr_text) => ocr_text.chars().take(2000).collect(),
+        Err(_) => text,
+    }
+}
+
+/// Try to extract structured information from the first page of a PDF,
+/// decrypting it with `password` if needed
+pub fn extract_document_info(
+    file_path: &str,
+    password: Option<&str>,
+) -> Result<ExtractedDocumentInfo, PdfError> {
+    extract_document_info_with_rules(file_path, password, &default_type_rules())
+}
+
+/// As [`extract_document_info`], detecting the document type with `rules`
+pub fn extract_document_info_with_rules(
+    file_path: &str,
+    password: Option<&str>,
+    rules: &[TypeRule],
+) -> Result<ExtractedDocumentInfo, PdfError> {
+    let doc = load_document(file_path, password)?;
+    let first_page = with_ocr_fallback(first_page_text(&doc, 2000)?, || {
+        ocr_page_text(file_path, password, 0)
+    });
     let text_lower = first_page.to_lowercase();
 
-    let mut info = ExtractedDocumentInfo::default();
-    info.first_page_text = Some(first_page.clone().chars().take(500).collect());
+    let needs_ocr = has_little_text(&first_page);
+    let mut info = ExtractedDocumentInfo {
+        first_page_text: if needs_ocr {
+            None
+        } else {
+            Some(first_page.chars().take(500).collect())
+        },
+        needs_ocr,
+        scanned: is_scanned_pdf(&doc),
+        ..Default::default()
+    };
 
-    // Try to detect document type
-    if text_lower.contains("affidavit") {
-        info.document_type = Some("Affidavit".to_string());
-    } else if text_lower.contains("exhibit") {
-        info.document_type = Some("Exhibit".to_string());
-    } else if text_lower.contains("contract") || text_lower.contains("agreement") {
-        info.document_type = Some("Contract".to_string());
-    } else if text_lower.contains("invoice") {
-        info.document_type = Some("Invoice".to_string());
-    } else if text_lower.contains("from:") && text_lower.contains("to:") {
-        info.document_type = Some("Email".to_string());
-    } else if text_lower.contains("letter") || text_lower.contains("dear") {
-        info.document_type = Some("Letter".to_string());
+    if let Some((document_type, confidence)) = detect_document_type(&text_lower, rules) {
+        info.document_type = Some(document_type);
+        info.document_type_confidence = Some(confidence);
     }
 
     // Try to extract email-style fields
@@ -81,7 +287,11 @@ pub fn extract_document_info(file_path: &str) -> Result<ExtractedDocumentInfo, S
         }
     }
 
+    info.suit_number = extract_suit_number(&first_page);
+    (info.plaintiff, info.defendant) = extract_parties(&first_page);
+
     // Try to find date patterns if not found in headers
+    info.dates = extract_dates_from_text(&first_page);
     if info.date.is_none() {
         info.date = extract_date_from_text(&first_page);
     }
@@ -89,45 +299,232 @@ pub fn extract_document_info(file_path: &str) -> Result<ExtractedDocumentInfo, S
     Ok(info)
 }
 
-/// Try to find a date in text using common patterns
-fn extract_date_from_text(text: &str) -> Option<String> {
-    let months = [
-        "january",
-        "february",
-        "march",
-        "april",
-        "may",
-        "june",
-        "july",
-        "august",
-        "september",
-        "october",
-        "november",
-        "december",
-    ];
-
-    let text_lower = text.to_lowercase();
-
-    // Look for "DD Month YYYY" pattern
-    for month in &months {
-        if let Some(idx) = text_lower.find(month) {
-            let start = idx.saturating_sub(5);
-            let end = (idx + month.len() + 10).min(text.len());
-            let date_region = &text[start..end];
-
-            let words: Vec<&str> = date_region.split_whitespace().collect();
-            if words.len() >= 3 {
-                return Some(date_region.trim().to_string());
+/// Month names and abbreviations, as a regex alternation
+const MONTHS: &str = "jan(?:uary)?|feb(?:ruary)?|mar(?:ch)?|apr(?:il)?|may|june?|july?|aug(?:ust)?|sep(?:t(?:ember)?)?|oct(?:ober)?|nov(?:ember)?|dec(?:ember)?";
+
+/// "15 January 2024", "January 15, 2024", "15/01/2024" and "2024-01-15"
+static DATE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)\b(?:(?P<d1>\d{{1,2}})(?:st|nd|rd|th)?\s+(?:{m})\.?,?\s+\d{{4}}|(?:{m})\.?\s+(?P<d2>\d{{1,2}})(?:st|nd|rd|th)?,?\s+\d{{4}}|(?P<nd>\d{{1,2}})[/.-](?P<nm>\d{{1,2}})[/.-](?P<ny>\d{{4}})|(?P<iy>\d{{4}})-(?P<im>\d{{2}})-(?P<id>\d{{2}}))\b",
+        m = MONTHS
+    ))
+    .expect("date pattern is valid")
+});
+
+/// Keywords that introduce a document's own date
+static DATE_KEYWORD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bdated?\b").expect("keyword pattern is valid"));
+
+/// "HC/OC 123/2024", "DC/OSS 456 / 2023", "HC / S 12/2020"
+static SUIT_NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(HC|DC|MC|CA|AD|SICC|FC|YC)\s*/\s*([A-Z]{1,5})\s*(?:No\.?\s*)?(\d{1,6})\s*/\s*(\d{4})\b")
+        .expect("suit number pattern is valid")
+});
+
+/// Older style: "Suit No. 123 of 2019", "Originating Summons No 45 of 2020"
+static SUIT_NUMBER_WORDS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(suit|originating (?:claim|application|summons)|civil appeal|summons)\s+no\.?\s*(\d{1,6})\s+of\s+(\d{4})\b")
+        .expect("suit number pattern is valid")
+});
+
+/// The first court case number in `text`, normalised to "HC/OC 123/2024"
+/// or "Suit No. 123 of 2019" form
+fn extract_suit_number(text: &str) -> Option<String> {
+    if let Some(caps) = SUIT_NUMBER.captures(text) {
+        return Some(format!(
+            "{}/{} {}/{}",
+            caps[1].to_uppercase(),
+            caps[2].to_uppercase(),
+            &caps[3],
+            &caps[4]
+        ));
+    }
+    SUIT_NUMBER_WORDS.captures(text).map(|caps| {
+        let kind = caps[1]
+            .split_whitespace()
+            .map(|word| {
+                // Case-insensitive matching lets non-ASCII letters through
+                // (e.g. "ſ" for "s"), so capitalise by char, not by byte
+                let word = word.to_lowercase();
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(" ");
+        format!("{} No. {} of {}", kind, &caps[2], &caps[3])
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PartyRole {
+    Plaintiff,
+    Defendant,
+}
+
+/// Role labels that close a party's block in a cause title
+const ROLE_LABELS: &[(&str, PartyRole)] = &[
+    ("plaintiff", PartyRole::Plaintiff),
+    ("claimant", PartyRole::Plaintiff),
+    ("defendant", PartyRole::Defendant),
+];
+
+/// Titles dropped from the front of party names
+const HONORIFICS: &[&str] = &["mr", "mrs", "ms", "mdm", "madam", "miss", "dr"];
+
+/// Split a trailing role label ("... Plaintiff", "...Defendants") off a line
+fn split_role_label(line: &str) -> (&str, Option<PartyRole>) {
+    let lower = line.to_ascii_lowercase();
+    let lower = lower.trim_end_matches("(s)").trim_end_matches('s');
+    for &(label, role) in ROLE_LABELS {
+        if let Some(prefix) = lower.strip_suffix(label) {
+            let name = line[..prefix.len()].trim_end();
+            let stripped = name.trim_end_matches(['.', '\u{2026}', ' ']);
+            // "Lim Plaintiff" is a name, "Lim ... Plaintiff" is a role line
+            if stripped.is_empty() || stripped.len() < name.len() {
+                return (stripped, Some(role));
             }
         }
     }
+    (line, None)
+}
 
-    None
+fn clean_party_name(name: &str) -> String {
+    let mut name = name.trim().trim_end_matches(',');
+    if let Some((first, rest)) = name.split_once(' ') {
+        if HONORIFICS.contains(&first.trim_end_matches('.').to_ascii_lowercase().as_str()) {
+            name = rest;
+        }
+    }
+    name.trim().to_string()
+}
+
+/// Plaintiff and defendant names from a cause title. Parentheticals such as
+/// "(NRIC No. ...)" are skipped; numbered co-parties are joined with "; ".
+fn extract_parties(text: &str) -> (Option<String>, Option<String>) {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    let Some(start) = lines.iter().position(|l| l.eq_ignore_ascii_case("between")) else {
+        return (None, None);
+    };
+
+    let (mut plaintiff, mut defendant) = (None, None);
+    let mut parties: Vec<String> = Vec::new();
+    for line in &lines[start + 1..] {
+        if line.eq_ignore_ascii_case("and") {
+            parties.clear();
+            continue;
+        }
+        let (name, role) = split_role_label(line);
+        if !name.is_empty() && !name.starts_with('(') {
+            let numbered = name.split_once(['.', ')']).is_some_and(|(n, _)| {
+                !n.is_empty()
+                    && n.trim_start_matches('(')
+                        .chars()
+                        .all(|c| c.is_ascii_digit())
+            });
+            let name = if numbered {
+                name.split_once(['.', ')']).map_or(name, |(_, rest)| rest)
+            } else {
+                name
+            };
+            match parties.last_mut() {
+                Some(last) if !numbered => {
+                    last.push(' ');
+                    last.push_str(name.trim());
+                }
+                _ => parties.push(name.trim().to_string()),
+            }
+        }
+
+        let Some(role) = role else { continue };
+        let names: Vec<String> = parties
+            .drain(..)
+            .map(|p| clean_party_name(&p))
+            .filter(|p| !p.is_empty())
+            .collect();
+        let names = (!names.is_empty()).then(|| names.join("; "));
+        match role {
+            PartyRole::Plaintiff if plaintiff.is_none() => plaintiff = names,
+            PartyRole::Defendant => {
+                defendant = names;
+                break;
+            }
+            _ => {}
+        }
+    }
+    (plaintiff, defendant)
+}
+
+/// Whether the day (and for numeric dates, month) of a match can exist
+fn is_valid_date(caps: &regex::Captures) -> bool {
+    let num = |name: &str| caps.name(name).and_then(|m| m.as_str().parse::<u32>().ok());
+    if let (Some(d), Some(m), Some(y)) = (num("nd"), num("nm"), num("ny")) {
+        // Singapore documents write numeric dates day first
+        return NaiveDate::from_ymd_opt(y as i32, m, d).is_some();
+    }
+    if let (Some(y), Some(m), Some(d)) = (num("iy"), num("im"), num("id")) {
+        return NaiveDate::from_ymd_opt(y as i32, m, d).is_some();
+    }
+    num("d1")
+        .or(num("d2"))
+        .is_some_and(|d| (1..=31).contains(&d))
+}
+
+/// Byte ranges of every valid date in `text`, in order of appearance
+fn find_dates(text: &str) -> Vec<Range<usize>> {
+    DATE_PATTERN
+        .captures_iter(text)
+        .filter(|caps| is_valid_date(caps))
+        .filter_map(|caps| caps.get(0).map(|m| m.range()))
+        .collect()
+}
+
+/// Every date found in `text`, as written
+fn extract_dates_from_text(text: &str) -> Vec<String> {
+    find_dates(text)
+        .into_iter()
+        .map(|range| text[range].to_string())
+        .collect()
+}
+
+/// The date most likely to be the document's own: the one nearest a
+/// "date"/"dated" keyword, else the first valid date
+fn extract_date_from_text(text: &str) -> Option<String> {
+    let dates = find_dates(text);
+    let keywords: Vec<Range<usize>> = DATE_KEYWORD.find_iter(text).map(|m| m.range()).collect();
+    let distance = |date: &Range<usize>| {
+        keywords
+            .iter()
+            .map(|keyword| {
+                if date.start >= keyword.end {
+                    date.start - keyword.end
+                } else {
+                    keyword.start.saturating_sub(date.end)
+                }
+            })
+            .min()
+    };
+
+    let best = if keywords.is_empty() {
+        dates.first()
+    } else {
+        dates.iter().min_by_key(|date| distance(date))
+    };
+    best.map(|range| text[range.clone()].to_string())
 }
 
 /// Generate an automatic description for a document based on extracted info
-pub fn generate_auto_description(file_path: &str) -> Result<String, String> {
-    let info = extract_document_info(file_path)?;
+pub fn generate_auto_description(
+    file_path: &str,
+    password: Option<&str>,
+) -> Result<String, PdfError> {
+    let info = extract_document_info(file_path, password)?;
 
     let mut parts = Vec::new();
 
@@ -154,9 +551,358 @@ pub fn generate_auto_description(file_path: &str) -> Result<String, String> {
                 preview
             });
         }
+        if info.needs_ocr {
+            return Ok("Scanned document (needs OCR)".to_string());
+        }
         return Ok("Document".to_string());
     }
 
     Ok(parts.join(" - "))
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::fixtures::{
+        temp_pdf_path, write_encrypted_pdf, write_image_only_pdf, write_text_pdf,
+    };
+
+    #[test]
+    fn test_image_only_pdf_needs_ocr() {
+        let path = temp_pdf_path("image-only");
+        write_image_only_pdf(&path);
+
+        let info = extract_document_info(path.to_str().unwrap(), None).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(info.needs_ocr);
+        assert!(info.scanned);
+        assert!(info.first_page_text.is_none());
+    }
+
+    #[test]
+    fn test_ocr_fallback_only_for_thin_text() {
+        let page = "AFFIDAVIT OF TAN AH KOW\nI, Tan Ah Kow, affirm as follows:";
+        let text = with_ocr_fallback(page.to_string(), || panic!("OCR should be skipped"));
+        assert_eq!(text, page);
+
+        let text = with_ocr_fallback("TAB 3".to_string(), || Ok("LETTER OF DEMAND".to_string()));
+        assert_eq!(text, "LETTER OF DEMAND");
+
+        let text = with_ocr_fallback("TAB 3".to_string(), || {
+            Err(PdfError::Unavailable("no OCR".to_string()))
+        });
+        assert_eq!(text, "TAB 3");
+    }
+
+    #[cfg(not(feature = "ocr"))]
+    #[test]
+    fn test_text_layer_used_without_ocr_feature() {
+        let path = temp_pdf_path("no-ocr-text");
+        write_text_pdf(
+            &path,
+            &["AFFIDAVIT OF TAN AH KOW\nI, Tan Ah Kow, affirm as follows:"],
+        );
+
+        let info = extract_document_info(path.to_str().unwrap(), None).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(!info.needs_ocr);
+        assert!(info
+            .first_page_text
+            .unwrap()
+            .starts_with("AFFIDAVIT OF TAN AH KOW"));
+        assert_eq!(info.document_type.as_deref(), Some("Affidavit"));
+    }
+
+    #[test]
+    fn test_page_with_only_a_stamp_needs_ocr() {
+        let path = temp_pdf_path("stamp-only");
+        write_text_pdf(&path, &["TAB 3"]);
+
+        let info = extract_document_info(path.to_str().unwrap(), None).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(info.needs_ocr);
+        assert!(info.first_page_text.is_none());
+    }
+
+    #[test]
+    fn test_encrypted_pdf_needs_password() {
+        let path = temp_pdf_path("info-encrypted");
+        write_encrypted_pdf(&path, "s3cret");
+        let path_str = path.to_str().unwrap();
+
+        let without = extract_document_info(path_str, None);
+        let with = extract_document_info(path_str, Some("s3cret"));
+        std::fs::remove_file(&path).ok();
+
+        assert!(matches!(without, Err(PdfError::Encrypted)));
+        assert!(!with.unwrap().scanned);
+    }
+
+    #[test]
+    fn test_text_pdf_does_not_need_ocr() {
+        let path = temp_pdf_path("text");
+        write_text_pdf(&path, &["Invoice No. 42\nDated 15 January 2024"]);
+
+        let info = extract_document_info(path.to_str().unwrap(), None).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(!info.needs_ocr);
+        assert!(!info.scanned);
+        assert!(info.first_page_text.unwrap().contains("Invoice No. 42"));
+        assert_eq!(info.document_type.as_deref(), Some("Invoice"));
+    }
+
+    #[test]
+    fn test_auto_description_flags_scans() {
+        let path = temp_pdf_path("scan-description");
+        write_image_only_pdf(&path);
+
+        let description = generate_auto_description(path.to_str().unwrap(), None).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(description, "Scanned document (needs OCR)");
+    }
+
+    #[test]
+    fn test_singapore_litigation_types() {
+        let cases = [
+            (
+                "WRIT OF SUMMONS\nThe Statement of Claim is endorsed",
+                "Writ of Summons",
+            ),
+            (
+                "ORIGINATING\nAPPLICATION No. 12 of 2024",
+                "Originating Application",
+            ),
+            (
+                "DEFENCE (AMENDMENT NO. 1)\n1. The Defendant denies",
+                "Defence",
+            ),
+            (
+                "NOTICE OF APPEAL\nTake notice that the Appellant",
+                "Notice of Appeal",
+            ),
+            (
+                "ORDER OF COURT\nIt is ordered that the affidavit",
+                "Order of Court",
+            ),
+            (
+                "AGREED BUNDLE OF DOCUMENTS\nTab 3: Affidavit of Tan",
+                "Bundle of Documents",
+            ),
+            (
+                "PLAINTIFF'S SKELETAL SUBMISSIONS on the defence",
+                "Skeletal Submissions",
+            ),
+            ("STATEMENT OF CLAIM\n1. The Plaintiff", "Statement of Claim"),
+            (
+                "From: tan@example.com\nTo: lim@example.com\nDear Lim",
+                "Email",
+            ),
+        ];
+        let rules = default_type_rules();
+        for (text, expected) in cases {
+            assert_eq!(
+                detect_document_type(&text.to_lowercase(), &rules).map(|(t, _)| t),
+                Some(expected.to_string()),
+                "{}",
+                text
+            );
+        }
+        assert_eq!(detect_document_type("minutes of meeting", &rules), None);
+    }
+
+    #[test]
+    fn test_heading_scores_higher_than_passing_mention() {
+        let heading = "AFFIDAVIT OF TAN AH KOW\n\nI, Tan Ah Kow, affirm as follows:";
+        let mention = "Dear Sirs\n\nThank you for your letter.\nWe will reply shortly.\n\
+                       Our client will file a further affidavit next week.";
+
+        let rules = default_type_rules();
+
+        let (heading_type, heading_score) =
+            detect_document_type(&heading.to_lowercase(), &rules).unwrap();
+        let (mention_type, mention_score) =
+            detect_document_type(&mention.to_lowercase(), &rules).unwrap();
+
+        assert_eq!(heading_type, "Affidavit");
+        assert_eq!(mention_type, "Affidavit");
+        assert!((heading_score - TITLE_WEIGHT).abs() < 1e-6);
+        assert!((mention_score - BODY_WEIGHT).abs() < 1e-6);
+
+        // A second matching phrase corroborates the first
+        let (_, contract_score) =
+            detect_document_type("sale agreement\nthis contract is made", &rules).unwrap();
+        assert!(contract_score > TITLE_WEIGHT);
+    }
+
+    #[test]
+    fn test_passing_mention_in_pdf_scores_as_body() {
+        let heading = temp_pdf_path("affidavit-heading");
+        let mention = temp_pdf_path("affidavit-mention");
+        write_text_pdf(
+            &heading,
+            &["AFFIDAVIT OF TAN AH KOW\nI, Tan Ah Kow, affirm as follows:"],
+        );
+        write_text_pdf(
+            &mention,
+            &[
+                "Dear Sirs\nThank you for your letter.\nWe will reply shortly.\n\
+               Our client will file a further affidavit next week.",
+            ],
+        );
+
+        let heading_info = extract_document_info(heading.to_str().unwrap(), None).unwrap();
+        let mention_info = extract_document_info(mention.to_str().unwrap(), None).unwrap();
+        std::fs::remove_file(&heading).ok();
+        std::fs::remove_file(&mention).ok();
+
+        assert_eq!(heading_info.document_type.as_deref(), Some("Affidavit"));
+        assert_eq!(mention_info.document_type.as_deref(), Some("Affidavit"));
+        let heading_score = heading_info.document_type_confidence.unwrap();
+        let mention_score = mention_info.document_type_confidence.unwrap();
+        assert!((heading_score - TITLE_WEIGHT).abs() < 1e-6);
+        assert!((mention_score - BODY_WEIGHT).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_custom_rules_extend_and_override_defaults() {
+        let text = "TENANCY AGREEMENT\nThis lease is made between".to_lowercase();
+        assert_eq!(
+            detect_document_type(&text, &default_type_rules()).map(|(t, _)| t),
+            Some("Contract".to_string())
+        );
+
+        // A new type ranked above Contract
+        let lease = TypeRule {
+            document_type: "Lease".to_string(),
+            keywords: vec!["Tenancy  Agreement".to_string(), "lease".to_string()],
+            priority: 45,
+            match_all: false,
+        };
+        let rules = merge_type_rules(vec![lease]);
+        assert_eq!(rules.len(), default_type_rules().len() + 1);
+        let (document_type, confidence) = detect_document_type(&text, &rules).unwrap();
+        assert_eq!(document_type, "Lease");
+        assert!(confidence > TITLE_WEIGHT);
+
+        // Replacing the built-in Invoice keywords
+        let invoice = TypeRule {
+            document_type: "invoice".to_string(),
+            keywords: vec!["tax invoice".to_string()],
+            priority: 30,
+            match_all: false,
+        };
+        let rules = merge_type_rules(vec![invoice]);
+        assert_eq!(rules.len(), default_type_rules().len());
+        assert_eq!(detect_document_type("invoice no. 42", &rules), None);
+    }
+
+    #[test]
+    fn test_suit_numbers() {
+        let cases = [
+            (
+                "IN THE GENERAL DIVISION OF THE HIGH COURT\nHC/OC 123/2024",
+                "HC/OC 123/2024",
+            ),
+            (
+                "In the District Court\nDC/OSS 456 / 2023\nBetween",
+                "DC/OSS 456/2023",
+            ),
+            ("hc / s 12/2020", "HC/S 12/2020"),
+            ("Court of Appeal CA/CA No. 7/2025", "CA/CA 7/2025"),
+            ("Suit No. 1021 of 2019", "Suit No. 1021 of 2019"),
+            (
+                "ORIGINATING SUMMONS NO 45 OF 2020",
+                "Originating Summons No. 45 of 2020",
+            ),
+            ("ſuit No. 12 of 2020", "Suit No. 12 of 2020"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(
+                extract_suit_number(text).as_deref(),
+                Some(expected),
+                "{}",
+                text
+            );
+        }
+
+        assert_eq!(
+            extract_suit_number("Invoice 2024/0012 dated 15/01/2024"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cause_title_parties() {
+        let text = "IN THE GENERAL DIVISION OF THE HIGH COURT OF THE REPUBLIC OF SINGAPORE\n\
+                    HC/OC 123/2024\n\
+                    Between\n\
+                    Mr Tan Ah Kow\n\
+                    (NRIC No. S1234567A)\n\
+                    ... Plaintiff\n\
+                    And\n\
+                    1. Lim Holdings Pte. Ltd.\n\
+                    (UEN No. 201912345K)\n\
+                    2. Dr. Lim Bee Hoon ...Defendants\n\
+                    AFFIDAVIT";
+        let (plaintiff, defendant) = extract_parties(text);
+        assert_eq!(plaintiff.as_deref(), Some("Tan Ah Kow"));
+        assert_eq!(
+            defendant.as_deref(),
+            Some("Lim Holdings Pte. Ltd.; Lim Bee Hoon")
+        );
+
+        assert_eq!(
+            extract_parties("Dear Sirs\nWe act for the Plaintiff"),
+            (None, None)
+        );
+    }
+
+    #[test]
+    fn test_cause_title_parties_from_pdf() {
+        let path = temp_pdf_path("cause-title");
+        write_text_pdf(
+            &path,
+            &["IN THE GENERAL DIVISION OF THE HIGH COURT\n\
+               HC/OC 123/2024\n\
+               Between\n\
+               Mr Tan Ah Kow\n\
+               ... Plaintiff\n\
+               And\n\
+               Lim Holdings Pte. Ltd.\n\
+               ... Defendant"],
+        );
+
+        let info = extract_document_info(path.to_str().unwrap(), None).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(info.suit_number.as_deref(), Some("HC/OC 123/2024"));
+        assert_eq!(info.plaintiff.as_deref(), Some("Tan Ah Kow"));
+        assert_eq!(info.defendant.as_deref(), Some("Lim Holdings Pte. Ltd."));
+    }
+
+    #[test]
+    fn test_date_nearest_keyword_is_preferred() {
+        let text = "Further to our letter of 3 March 2024,\nwe enclose the agreement dated 15th January, 2024.";
+        assert_eq!(
+            extract_date_from_text(text).as_deref(),
+            Some("15th January, 2024")
+        );
+        assert_eq!(
+            extract_dates_from_text(text),
+            vec!["3 March 2024", "15th January, 2024"]
+        );
+    }
+
+    #[test]
+    fn test_first_valid_date_without_keyword() {
+        let text = "Meeting on 31/02/2024 was moved to 01/03/2024 and then June 5, 2024.";
+        assert_eq!(extract_date_from_text(text).as_deref(), Some("01/03/2024"));
+        assert_eq!(extract_dates_from_text(text).len(), 2);
+        assert_eq!(extract_date_from_text("Mayday at 10am"), None);
+    }
+}
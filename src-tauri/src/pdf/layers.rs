@@ -0,0 +1,400 @@
+//! Optional content (layer) detection and flattening
+//!
+//! Optional content groups can render on screen but be hidden or skipped
+//! when printed. Flattening bakes the default view into plain content so a
+//! filed bundle prints exactly what counsel saw.
+
+use lopdf::content::{Content, Operation};
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use std::collections::HashSet;
+
+fn load_document(file_path: &str) -> Result<Document, String> {
+    Document::load(file_path).map_err(|e| format!("Failed to load PDF: {}", e))
+}
+
+/// Resolve a value that may be an indirect reference to a dictionary
+fn deref_dict<'a>(doc: &'a Document, object: &'a Object) -> Option<&'a Dictionary> {
+    match object {
+        Object::Dictionary(dict) => Some(dict),
+        Object::Stream(stream) => Some(&stream.dict),
+        Object::Reference(id) => match doc.get_object(*id).ok()? {
+            Object::Dictionary(dict) => Some(dict),
+            Object::Stream(stream) => Some(&stream.dict),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// All resource dictionaries that apply to a page, including inherited ones
+fn page_resources(doc: &Document, page_id: ObjectId) -> Vec<&Dictionary> {
+    let (inline, ids) = doc.get_page_resources(page_id);
+    inline
+        .into_iter()
+        .chain(ids.into_iter().filter_map(|id| doc.get_dictionary(id).ok()))
+        .collect()
+}
+
+/// Look up a named entry in one resource category (e.g. `XObject`, `Properties`)
+fn resource_entry<'a>(
+    doc: &'a Document,
+    resources: &[&'a Dictionary],
+    category: &[u8],
+    name: &[u8],
+) -> Option<&'a Object> {
+    resources.iter().find_map(|res| {
+        let category = deref_dict(doc, res.get(category).ok()?)?;
+        category.get(name).ok()
+    })
+}
+
+fn is_optional_content_marker(op: &Operation) -> bool {
+    op.operator == "BDC" && matches!(op.operands.first(), Some(Object::Name(tag)) if tag == b"OC")
+}
+
+fn page_uses_optional_content(doc: &Document, page_id: ObjectId) -> bool {
+    if let Ok(content) = doc.get_and_decode_page_content(page_id) {
+        if content.operations.iter().any(is_optional_content_marker) {
+            return true;
+        }
+    }
+
+    let resources = page_resources(doc, page_id);
+    let xobject_in_layer = resources.iter().any(|res| {
+        res.get(b"XObject")
+            .ok()
+            .and_then(|xobjects| deref_dict(doc, xobjects))
+            .is_some_and(|xobjects| {
+                xobjects
+                    .iter()
+                    .filter_map(|(_, xobject)| deref_dict(doc, xobject))
+                    .any(|xobject| xobject.has(b"OC"))
+            })
+    });
+
+    xobject_in_layer
+        || doc
+            .get_page_annotations(page_id)
+            .iter()
+            .any(|annot| annot.has(b"OC"))
+}
+
+/// Page numbers (1-based) that reference optional content layers
+pub fn detect_optional_content(file_path: &str) -> Result<Vec<usize>, String> {
+    let doc = load_document(file_path)?;
+    Ok(doc
+        .get_pages()
+        .into_iter()
+        .filter(|(_, page_id)| page_uses_optional_content(&doc, *page_id))
+        .map(|(page_number, _)| page_number as usize)
+        .collect())
+}
+
+/// Optional content groups hidden in the document's default configuration
+fn hidden_groups(doc: &Document) -> HashSet<ObjectId> {
+    let Some(oc_properties) = doc
+        .catalog()
+        .ok()
+        .and_then(|catalog| catalog.get(b"OCProperties").ok())
+        .and_then(|props| deref_dict(doc, props))
+    else {
+        return HashSet::new();
+    };
+    let Some(default_config) = oc_properties
+        .get(b"D")
+        .ok()
+        .and_then(|d| deref_dict(doc, d))
+    else {
+        return HashSet::new();
+    };
+
+    let refs = |dict: &Dictionary, key: &[u8]| -> HashSet<ObjectId> {
+        dict.get(key)
+            .and_then(Object::as_array)
+            .map(|items| items.iter().filter_map(|o| o.as_reference().ok()).collect())
+            .unwrap_or_default()
+    };
+
+    let base_state_off = matches!(
+        default_config.get(b"BaseState"),
+        Ok(Object::Name(state)) if state == b"OFF"
+    );
+    if base_state_off {
+        let on = refs(default_config, b"ON");
+        refs(oc_properties, b"OCGs")
+            .into_iter()
+            .filter(|id| !on.contains(id))
+            .collect()
+    } else {
+        refs(default_config, b"OFF")
+    }
+}
+
+fn is_hidden(dict: &Dictionary, hidden: &HashSet<ObjectId>) -> bool {
+    dict.get(b"OC")
+        .and_then(Object::as_reference)
+        .is_ok_and(|id| hidden.contains(&id))
+}
+
+/// Rewrite a page's content: drop hidden layers, unwrap visible ones
+fn flatten_page_content(
+    doc: &Document,
+    page_id: ObjectId,
+    hidden: &HashSet<ObjectId>,
+) -> Result<Option<Vec<u8>>, String> {
+    enum Marked {
+        Keep,
+        Unwrap,
+    }
+
+    let content = doc
+        .get_and_decode_page_content(page_id)
+        .map_err(|e| format!("Failed to decode page content: {}", e))?;
+    let resources = page_resources(doc, page_id);
+
+    let mut operations = Vec::with_capacity(content.operations.len());
+    let mut stack: Vec<Marked> = Vec::new();
+    let mut skip_depth = 0usize;
+    let mut changed = false;
+
+    for op in content.operations {
+        if skip_depth > 0 {
+            match op.operator.as_str() {
+                "BMC" | "BDC" => skip_depth += 1,
+                "EMC" => skip_depth -= 1,
+                _ => {}
+            }
+            continue;
+        }
+
+        match op.operator.as_str() {
+            "BDC" if is_optional_content_marker(&op) => {
+                changed = true;
+                let group_hidden = match op.operands.get(1) {
+                    Some(Object::Name(name)) => {
+                        resource_entry(doc, &resources, b"Properties", name)
+                            .and_then(|group| group.as_reference().ok())
+                            .is_some_and(|id| hidden.contains(&id))
+                    }
+                    _ => false,
+                };
+                if group_hidden {
+                    skip_depth = 1;
+                } else {
+                    stack.push(Marked::Unwrap);
+                }
+            }
+            "BMC" | "BDC" => {
+                stack.push(Marked::Keep);
+                operations.push(op);
+            }
+            "EMC" => match stack.pop() {
+                Some(Marked::Unwrap) => {}
+                _ => operations.push(op),
+            },
+            "Do" => {
+                let xobject_hidden = match op.operands.first() {
+                    Some(Object::Name(name)) => resource_entry(doc, &resources, b"XObject", name)
+                        .and_then(|xobject| deref_dict(doc, xobject))
+                        .is_some_and(|xobject| is_hidden(xobject, hidden)),
+                    _ => false,
+                };
+                if xobject_hidden {
+                    changed = true;
+                } else {
+                    operations.push(op);
+                }
+            }
+            _ => operations.push(op),
+        }
+    }
+
+    if !changed {
+        return Ok(None);
+    }
+    Content { operations }
+        .encode()
+        .map(Some)
+        .map_err(|e| format!("Failed to encode page content: {}", e))
+}
+
+/// Remove optional content membership from a dictionary and its nested dictionaries
+fn strip_optional_content(dict: &mut Dictionary, groups: &HashSet<ObjectId>) {
+    dict.remove(b"OC");
+
+    if let Ok(Object::Dictionary(properties)) = dict.get_mut(b"Properties") {
+        let layer_names: Vec<Vec<u8>> = properties
+            .iter()
+            .filter(|(_, value)| matches!(value, Object::Reference(id) if groups.contains(id)))
+            .map(|(name, _)| name.clone())
+            .collect();
+        for name in layer_names {
+            properties.remove(&name);
+        }
+    }
+
+    for (_, value) in dict.iter_mut() {
+        if let Object::Dictionary(nested) = value {
+            strip_optional_content(nested, groups);
+        }
+    }
+}
+
+/// Bake a PDF's default-visible layers into plain content.
+///
+/// Content in layers that are hidden by default is removed, visible layers
+/// are unwrapped, and the catalog's `/OCProperties` is dropped so every
+/// remaining object prints unconditionally. Returns the flattened page numbers.
+pub fn flatten_optional_content(input_path: &str, output_path: &str) -> Result<Vec<usize>, String> {
+    let mut doc = load_document(input_path)?;
+    let hidden = hidden_groups(&doc);
+
+    let mut flattened = Vec::new();
+    for (page_number, page_id) in doc.get_pages() {
+        // Hidden annotations would become visible once /OC is stripped
+        let hidden_annots: Vec<ObjectId> = match doc
+            .get_dictionary(page_id)
+            .and_then(|page| page.get(b"Annots"))
+        {
+            Ok(Object::Array(annots)) => annots
+                .iter()
+                .filter_map(|a| a.as_reference().ok())
+                .filter(|id| {
+                    doc.get_dictionary(*id)
+                        .is_ok_and(|annot| is_hidden(annot, &hidden))
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let new_content = flatten_page_content(&doc, page_id, &hidden)?;
+        if new_content.is_none() && hidden_annots.is_empty() {
+            continue;
+        }
+
+        if let Some(content) = new_content {
+            doc.change_page_content(page_id, content)
+                .map_err(|e| format!("Failed to update page content: {}", e))?;
+        }
+        if !hidden_annots.is_empty() {
+            if let Ok(Object::Array(annots)) = doc
+                .get_dictionary_mut(page_id)
+                .and_then(|page| page.get_mut(b"Annots"))
+            {
+                annots.retain(|a| !a.as_reference().is_ok_and(|id| hidden_annots.contains(&id)));
+            }
+        }
+        flattened.push(page_number as usize);
+    }
+
+    let groups: HashSet<ObjectId> = doc
+        .objects
+        .iter()
+        .filter(|(_, object)| {
+            object
+                .as_dict()
+                .is_ok_and(|dict| dict.type_is(b"OCG") || dict.type_is(b"OCMD"))
+        })
+        .map(|(id, _)| *id)
+        .collect();
+    for object in doc.objects.values_mut() {
+        match object {
+            Object::Dictionary(dict) => strip_optional_content(dict, &groups),
+            Object::Stream(stream) => strip_optional_content(&mut stream.dict, &groups),
+            _ => {}
+        }
+    }
+    if let Ok(catalog) = doc.catalog_mut() {
+        catalog.remove(b"OCProperties");
+    }
+
+    doc.save(output_path)
+        .map_err(|e| format!("Failed to save flattened PDF: {}", e))?;
+    Ok(flattened)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::fixtures::{build_pdf, temp_pdf_path};
+    use crate::pdf::text::extract_page_text;
+    use lopdf::dictionary;
+
+    /// Page 1 draws "Visible" in a default-on layer and "Draft" in a hidden
+    /// layer; page 2 has no layers.
+    fn write_layered_pdf(path: &std::path::PathBuf) {
+        let font = dictionary! {
+            "F1" => dictionary! {
+                "Type" => "Font",
+                "Subtype" => "Type1",
+                "BaseFont" => "Helvetica",
+            },
+        };
+        let mut doc = build_pdf(vec![
+            (
+                b"/OC /L1 BDC BT /F1 12 Tf 72 770 Td (Visible) Tj ET EMC \
+                  /OC /L2 BDC BT /F1 12 Tf 72 740 Td (Draft) Tj ET EMC"
+                    .to_vec(),
+                dictionary! { "Font" => font.clone() },
+            ),
+            (
+                b"BT /F1 12 Tf 72 770 Td (Plain) Tj ET".to_vec(),
+                dictionary! { "Font" => font },
+            ),
+        ]);
+
+        let visible = doc.add_object(
+            dictionary! { "Type" => "OCG", "Name" => Object::string_literal("Visible") },
+        );
+        let draft = doc
+            .add_object(dictionary! { "Type" => "OCG", "Name" => Object::string_literal("Draft") });
+
+        let first_page = doc.page_iter().next().unwrap();
+        let page = doc.get_dictionary_mut(first_page).unwrap();
+        let resources = page.get_mut(b"Resources").unwrap().as_dict_mut().unwrap();
+        resources.set("Properties", dictionary! { "L1" => visible, "L2" => draft });
+
+        doc.catalog_mut().unwrap().set(
+            "OCProperties",
+            dictionary! {
+                "OCGs" => vec![visible.into(), draft.into()],
+                "D" => dictionary! { "OFF" => vec![draft.into()] },
+            },
+        );
+        doc.save(path).unwrap();
+    }
+
+    #[test]
+    fn test_detect_optional_content_flags_layered_page() {
+        let path = temp_pdf_path("layers");
+        write_layered_pdf(&path);
+
+        let pages = detect_optional_content(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(pages, vec![1]);
+    }
+
+    #[test]
+    fn test_flatten_optional_content_keeps_default_view() {
+        let input = temp_pdf_path("layers-in");
+        let output = temp_pdf_path("layers-out");
+        write_layered_pdf(&input);
+
+        let flattened =
+            flatten_optional_content(input.to_str().unwrap(), output.to_str().unwrap()).unwrap();
+        assert_eq!(flattened, vec![1]);
+
+        let doc = Document::load(&output).unwrap();
+        let first_page = doc.page_iter().next().unwrap();
+        let text = extract_page_text(&doc, first_page).unwrap();
+        assert_eq!(text, "Visible");
+        assert!(!doc.catalog().unwrap().has(b"OCProperties"));
+        assert!(detect_optional_content(output.to_str().unwrap())
+            .unwrap()
+            .is_empty());
+
+        std::fs::remove_file(&input).ok();
+        std::fs::remove_file(&output).ok();
+    }
+}
@@ -0,0 +1,77 @@
+//! Document loading with encryption handling
+
+use lopdf::encryption::DecryptionError;
+use lopdf::{Document, Error};
+
+use super::error::PdfError;
+use super::validate::check_pdf_header;
+
+/// Load a PDF, decrypting it when it is encrypted.
+///
+/// Without a password, an empty user password is tried first since many
+/// "encrypted" PDFs only restrict permissions. Returns `PdfError::Encrypted`
+/// when a password is needed and `PdfError::IncorrectPassword` when the
+/// supplied one is wrong.
+pub fn load_document(file_path: &str, password: Option<&str>) -> Result<Document, PdfError> {
+    if !std::path::Path::new(file_path).exists() {
+        return Err(PdfError::NotFound(file_path.to_string()));
+    }
+    check_pdf_header(file_path).map_err(PdfError::Invalid)?;
+
+    let mut doc = Document::load(file_path)
+        .map_err(|e| PdfError::Invalid(format!("Not a valid PDF: {}", e)))?;
+    if !doc.is_encrypted() {
+        return Ok(doc);
+    }
+
+    match doc.decrypt(password.unwrap_or("")) {
+        Ok(()) => Ok(doc),
+        Err(Error::Decryption(DecryptionError::IncorrectPassword)) => Err(match password {
+            Some(_) => PdfError::IncorrectPassword,
+            None => PdfError::Encrypted,
+        }),
+        Err(e) => Err(PdfError::Invalid(format!("Failed to decrypt PDF: {}", e))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::fixtures::{temp_pdf_path, write_encrypted_pdf, write_text_pdf};
+
+    #[test]
+    fn test_encrypted_pdf_needs_password() {
+        let path = temp_pdf_path("encrypted");
+        write_encrypted_pdf(&path, "s3cret");
+        let path_str = path.to_str().unwrap();
+
+        let without = load_document(path_str, None);
+        let wrong = load_document(path_str, Some("guess"));
+        let right = load_document(path_str, Some("s3cret"));
+        std::fs::remove_file(&path).ok();
+
+        assert!(matches!(without, Err(PdfError::Encrypted)));
+        assert!(matches!(wrong, Err(PdfError::IncorrectPassword)));
+        assert_eq!(right.unwrap().get_pages().len(), 2);
+    }
+
+    #[test]
+    fn test_empty_user_password_opens_without_prompt() {
+        let encrypted = temp_pdf_path("encrypted-open");
+        let plain = temp_pdf_path("plain");
+        write_encrypted_pdf(&encrypted, "");
+        write_text_pdf(&plain, &["Page"]);
+
+        let opened = load_document(encrypted.to_str().unwrap(), None);
+        let plain_doc = load_document(plain.to_str().unwrap(), Some("ignored"));
+        std::fs::remove_file(&encrypted).ok();
+        std::fs::remove_file(&plain).ok();
+
+        assert!(opened.is_ok());
+        assert!(plain_doc.is_ok());
+        assert!(matches!(
+            load_document("/no/such/file.pdf", None),
+            Err(PdfError::NotFound(_))
+        ));
+    }
+}
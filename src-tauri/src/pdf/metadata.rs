@@ -1,73 +1,304 @@
 //! PDF metadata extraction
 
-use lopdf::Document;
+use chrono::{FixedOffset, NaiveDate, TimeZone};
+use lopdf::{Document, Object};
 use serde::{Deserialize, Serialize};
 use std::fs;
 
+use super::error::PdfError;
+use super::load::load_document;
+use super::text::extract_page_text;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PdfMetadata {
     pub page_count: usize,
     pub title: Option<String>,
+    pub author: Option<String>,
+    /// RFC 3339 when the PDF date parses, otherwise the raw /CreationDate value
+    pub creation_date: Option<String>,
     pub file_size: u64,
+    /// Whitespace-delimited words across all pages; `None` when not counted
+    pub word_count: Option<usize>,
+    /// Non-whitespace characters across all pages; `None` when not counted
+    pub char_count: Option<usize>,
+}
+
+/// Decode a PDF text string: UTF-16BE when it starts with a byte order mark,
+/// otherwise PDFDocEncoding (read as Latin-1, which it matches for printable text)
+fn decode_text_string(bytes: &[u8]) -> String {
+    match bytes.strip_prefix(&[0xFE, 0xFF]) {
+        Some(utf16) => {
+            let units: Vec<u16> = utf16
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        None => bytes.iter().map(|&b| char::from(b)).collect(),
+    }
+}
+
+/// Read a non-empty text entry from the trailer's /Info dictionary
+fn info_string(doc: &Document, key: &[u8]) -> Option<String> {
+    let info = match doc.trailer.get(b"Info").ok()? {
+        Object::Reference(id) => doc.get_dictionary(*id).ok()?,
+        Object::Dictionary(dict) => dict,
+        _ => return None,
+    };
+    let value = match info.get(key).ok()? {
+        Object::Reference(id) => doc.get_object(*id).ok()?,
+        value => value,
+    };
+    let text = decode_text_string(value.as_str().ok()?);
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
 }
 
-/// Extract metadata from a PDF file
-pub fn extract_pdf_metadata(file_path: &str) -> Result<PdfMetadata, String> {
-    println!("[pdf] extract_pdf_metadata called for: {}", file_path);
+/// Parse a PDF date (`D:YYYYMMDDHHmmSSOHH'mm'`) into RFC 3339.
+/// Everything after the year is optional; a missing offset is taken as UTC.
+fn parse_pdf_date(raw: &str) -> Option<String> {
+    let value = raw.trim();
+    let value = value.strip_prefix("D:").unwrap_or(value);
 
-    // Check if file exists
-    if !std::path::Path::new(file_path).exists() {
-        let err_msg = format!("File not found: {}", file_path);
-        println!("[pdf] Error: {}", err_msg);
-        return Err(err_msg);
+    let digits_end = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (digits, zone) = value.split_at(digits_end);
+    if digits.len() < 4 || digits.len() > 14 || digits.len() % 2 != 0 {
+        return None;
     }
-    println!("[pdf] File exists, proceeding with metadata extraction");
+    // An offset only follows a complete timestamp
+    if !zone.is_empty() && digits.len() != 14 {
+        return None;
+    }
+
+    let field = |start: usize, default: u32| -> Option<u32> {
+        match digits.get(start..start + 2) {
+            Some(part) => part.parse().ok(),
+            None => Some(default),
+        }
+    };
+    let year: i32 = digits[..4].parse().ok()?;
+    let date = NaiveDate::from_ymd_opt(year, field(4, 1)?, field(6, 1)?)?;
+    let datetime = date.and_hms_opt(field(8, 0)?, field(10, 0)?, field(12, 0)?)?;
+
+    let offset_seconds = match zone.chars().next() {
+        None | Some('Z') => 0,
+        Some(sign @ ('+' | '-')) => {
+            let zone_digits: String = zone[1..].chars().filter(char::is_ascii_digit).collect();
+            let hours: i32 = zone_digits.get(..2)?.parse().ok()?;
+            let minutes: i32 = zone_digits.get(2..4).unwrap_or("00").parse().ok()?;
+            let seconds = hours * 3600 + minutes * 60;
+            if sign == '-' {
+                -seconds
+            } else {
+                seconds
+            }
+        }
+        _ => return None,
+    };
+
+    let offset = FixedOffset::east_opt(offset_seconds)?;
+    let parsed = offset.from_local_datetime(&datetime).single()?;
+    Some(parsed.to_rfc3339())
+}
+
+/// Words and non-whitespace characters in the text of every page.
+/// Pages whose text can't be extracted count as empty.
+fn count_text(doc: &Document) -> (usize, usize) {
+    doc.page_iter()
+        .map(|page_id| extract_page_text(doc, page_id).unwrap_or_default())
+        .fold((0, 0), |(words, chars), text| {
+            (
+                words + text.split_whitespace().count(),
+                chars + text.chars().filter(|c| !c.is_whitespace()).count(),
+            )
+        })
+}
+
+/// Extract metadata from a PDF file, decrypting it with `password` if needed.
+/// Counting words means reading every page, so large files can skip it.
+pub fn extract_pdf_metadata(
+    file_path: &str,
+    password: Option<&str>,
+    count_words: bool,
+) -> Result<PdfMetadata, PdfError> {
+    // Load PDF document (also checks the file exists)
+    let doc = load_document(file_path, password)?;
 
     // Get file size
     let file_size = fs::metadata(file_path)
-        .map_err(|e| {
-            let err_msg = format!("Failed to read file metadata: {}", e);
-            println!("[pdf] Error: {}", err_msg);
-            err_msg
-        })?
+        .map_err(|e| PdfError::Invalid(format!("Failed to read file metadata: {}", e)))?
         .len();
-    println!("[pdf] File size: {} bytes", file_size);
-
-    // Load PDF document
-    let doc = Document::load(file_path).map_err(|e| {
-        let err_msg = format!("Not a valid PDF: {}", e);
-        println!("[pdf] Error: {}", err_msg);
-        err_msg
-    })?;
-    println!("[pdf] PDF loaded successfully");
 
     // Extract page count
     let pages = doc.get_pages();
     let page_count = pages.len();
-    println!("[pdf] Page count: {}", page_count);
 
-    // TODO: Implement proper title extraction if needed
-    let title = None;
+    let title = info_string(&doc, b"Title");
+    let author = info_string(&doc, b"Author");
+    let creation_date =
+        info_string(&doc, b"CreationDate").map(|raw| parse_pdf_date(&raw).unwrap_or(raw));
+
+    let (word_count, char_count) = if count_words {
+        let (words, chars) = count_text(&doc);
+        (Some(words), Some(chars))
+    } else {
+        (None, None)
+    };
 
-    let metadata = PdfMetadata {
+    Ok(PdfMetadata {
         page_count,
         title,
+        author,
+        creation_date,
         file_size,
-    };
-    println!("[pdf] Metadata extraction complete: {:?}", metadata);
-
-    Ok(metadata)
+        word_count,
+        char_count,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::pdf::fixtures::{build_pdf, temp_pdf_path, write_text_pdf};
+    use lopdf::{dictionary, Dictionary, StringFormat};
+
+    fn write_pdf_with_info(path: &std::path::PathBuf, info: Dictionary) {
+        let mut doc = build_pdf(vec![(b"BT ET".to_vec(), Dictionary::new())]);
+        let info_id = doc.add_object(info);
+        doc.trailer.set("Info", info_id);
+        doc.save(path).unwrap();
+    }
+
+    #[test]
+    fn test_extract_metadata_title() {
+        let path = temp_pdf_path("title");
+        write_pdf_with_info(
+            &path,
+            dictionary! { "Title" => Object::string_literal("Affidavit of Tan Ah Kow") },
+        );
+
+        let metadata = extract_pdf_metadata(path.to_str().unwrap(), None, false).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(metadata.title.as_deref(), Some("Affidavit of Tan Ah Kow"));
+    }
+
+    #[test]
+    fn test_extract_metadata_utf16_title() {
+        let mut bytes = vec![0xFE, 0xFF];
+        for unit in "Exhibit — Zoë".encode_utf16() {
+            bytes.extend_from_slice(&unit.to_be_bytes());
+        }
+        let path = temp_pdf_path("utf16-title");
+        write_pdf_with_info(
+            &path,
+            dictionary! { "Title" => Object::String(bytes, StringFormat::Hexadecimal) },
+        );
+
+        let metadata = extract_pdf_metadata(path.to_str().unwrap(), None, false).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(metadata.title.as_deref(), Some("Exhibit — Zoë"));
+    }
+
+    #[test]
+    fn test_extract_metadata_author_and_creation_date() {
+        let path = temp_pdf_path("author");
+        write_pdf_with_info(
+            &path,
+            dictionary! {
+                "Author" => Object::string_literal("Lee & Partners LLP"),
+                "CreationDate" => Object::string_literal("D:20240315093000+08'00'"),
+            },
+        );
+
+        let metadata = extract_pdf_metadata(path.to_str().unwrap(), None, false).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(metadata.author.as_deref(), Some("Lee & Partners LLP"));
+        assert_eq!(
+            metadata.creation_date.as_deref(),
+            Some("2024-03-15T09:30:00+08:00")
+        );
+    }
+
+    #[test]
+    fn test_parse_pdf_date() {
+        assert_eq!(
+            parse_pdf_date("D:20240315093000Z").as_deref(),
+            Some("2024-03-15T09:30:00+00:00")
+        );
+        assert_eq!(
+            parse_pdf_date("D:20231201").as_deref(),
+            Some("2023-12-01T00:00:00+00:00")
+        );
+        assert_eq!(
+            parse_pdf_date("D:20240101120000-05'30'").as_deref(),
+            Some("2024-01-01T12:00:00-05:30")
+        );
+    }
+
+    #[test]
+    fn test_malformed_creation_date_falls_back_to_raw() {
+        assert_eq!(parse_pdf_date("D:2024-13-45"), None);
+        assert_eq!(parse_pdf_date("D:20241345"), None);
+        assert_eq!(parse_pdf_date("yesterday"), None);
+
+        let path = temp_pdf_path("bad-date");
+        write_pdf_with_info(
+            &path,
+            dictionary! { "CreationDate" => Object::string_literal("sometime in March") },
+        );
+
+        let metadata = extract_pdf_metadata(path.to_str().unwrap(), None, false).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(metadata.creation_date.as_deref(), Some("sometime in March"));
+    }
+
+    #[test]
+    fn test_word_and_char_counts() {
+        let path = temp_pdf_path("word-count");
+        write_text_pdf(
+            &path,
+            &[
+                "Letter of demand\nDated 15 January 2024",
+                "Yours faithfully",
+            ],
+        );
+
+        let counted = extract_pdf_metadata(path.to_str().unwrap(), None, true).unwrap();
+        let skipped = extract_pdf_metadata(path.to_str().unwrap(), None, false).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(counted.word_count, Some(9));
+        assert_eq!(counted.char_count, Some(47));
+        assert_eq!(skipped.word_count, None);
+        assert_eq!(skipped.char_count, None);
+    }
+
     #[test]
     fn test_extract_metadata_file_not_found() {
-        let result = extract_pdf_metadata("/non/existent/file.pdf");
+        let result = extract_pdf_metadata("/non/existent/file.pdf", None, false);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("File not found"));
+        assert!(result.unwrap_err().to_string().contains("File not found"));
     }
-}
 
+    #[test]
+    fn test_extract_metadata_encrypted() {
+        let path = temp_pdf_path("meta-encrypted");
+        crate::pdf::fixtures::write_encrypted_pdf(&path, "s3cret");
+
+        let locked = extract_pdf_metadata(path.to_str().unwrap(), None, false);
+        let unlocked = extract_pdf_metadata(path.to_str().unwrap(), Some("s3cret"), false);
+        std::fs::remove_file(&path).ok();
+
+        let error = locked.unwrap_err();
+        assert!(matches!(error, PdfError::Encrypted));
+        assert_eq!(serde_json::to_value(&error).unwrap()["kind"], "encrypted");
+        assert_eq!(unlocked.unwrap().page_count, 2);
+    }
+}
@@ -4,12 +4,72 @@
 //! - metadata: PDF metadata extraction
 //! - text: Text extraction from PDF content
 //! - heuristics: Document type detection and date parsing
+//! - layers: Optional content (layer) detection and flattening
+//! - check: Output sanity checks for compiled PDFs
+//! - certificate: Certificate of accuracy front or back matter
+//! - endorsement: Closing endorsement page for filed bundles
+//! - positions: Per-word text positions for search highlighting
+//! - phash: Perceptual hashing of scanned pages for visual duplicate detection
+//! - validate: Pre-import validation of PDF files
+//! - collision: Existing text under a planned stamp position
+//! - names: Capitalised name extraction and the name index PDF
+//! - error: Structured errors for PDF loading
+//! - load: Document loading with encryption handling
+//! - stamp: Text stamps drawn over existing pages
+//! - bates: Bates numbering for discovery bundles
+//! - exhibit: Exhibit stamps on the first page of affidavit exhibits
+//! - cover: Bundle cover page
+//! - thumbnail: PNG page thumbnails (PDFium, behind the `thumbnails` feature)
+//! - scanned: Image-only (scanned) PDF detection
+//! - ocr: Tesseract OCR of scanned pages (behind the `ocr` feature)
+//! - hash: Content and visual hashes recorded for imported files
 
+mod bates;
+mod certificate;
+mod check;
+mod collision;
+mod cover;
+mod endorsement;
+mod error;
+mod exhibit;
+mod hash;
 mod heuristics;
+mod layers;
+mod load;
 mod metadata;
+mod names;
+mod ocr;
+mod phash;
+mod positions;
+mod scanned;
+mod stamp;
 mod text;
+mod thumbnail;
+mod validate;
 
-pub use heuristics::{extract_document_info, generate_auto_description, ExtractedDocumentInfo};
+#[cfg(test)]
+pub(crate) mod fixtures;
+
+pub use bates::{inject_bates_numbers, BatesStyle};
+pub use certificate::{generate_certificate, CertificateInfo, CertificatePosition};
+pub use check::{self_check, SelfCheckResult};
+pub use collision::{detect_stamp_collision, StampCorner};
+pub use cover::{generate_cover_pdf, prepend_cover_page, CoverInfo};
+pub use endorsement::{append_endorsement_page, generate_endorsement_page, EndorsementInfo};
+pub use error::PdfError;
+pub use exhibit::inject_exhibit_stamp;
+pub use hash::{file_hashes, FileHashes};
+pub use heuristics::{
+    default_type_rules, extract_document_info, extract_document_info_with_rules,
+    generate_auto_description, merge_type_rules, ExtractedDocumentInfo, TypeRule,
+};
+pub use layers::{detect_optional_content, flatten_optional_content};
+pub use load::load_document;
 pub use metadata::{extract_pdf_metadata, PdfMetadata};
-pub use text::extract_first_page_text;
+pub use names::{build_name_index, write_name_index_pdf, NameIndexEntry, NameIndexSource};
+pub use ocr::extract_text_ocr;
+pub use phash::{hamming_distance, page_phash};
+pub use positions::{extract_text_with_positions, TextSpan};
+pub use thumbnail::render_page_thumbnail;
+pub use validate::{validate_folder, validate_pdf, PdfValidation};
 
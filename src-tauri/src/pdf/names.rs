@@ -0,0 +1,270 @@
+//! Name index extraction
+//!
+//! Finds runs of two or more capitalised words ("Tan Ah Kow", "Lim Holdings")
+//! in page text. This is a heuristic: sentence-initial words and headings will
+//! produce some noise, which callers can suppress with an exclude list.
+
+use lopdf::Object;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::endorsement::{
+    empty_document, encode_pdf_text, insert_text_page, media_box_size, A4_MEDIA_BOX,
+};
+use super::text::extract_page_text;
+
+/// Words that never start or end a name
+const STOPWORDS: &[&str] = &[
+    "A",
+    "An",
+    "And",
+    "As",
+    "At",
+    "By",
+    "Dear",
+    "Dr",
+    "For",
+    "From",
+    "In",
+    "Mr",
+    "Mrs",
+    "Ms",
+    "Of",
+    "On",
+    "Re",
+    "The",
+    "To",
+    "With",
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// Index lines per page of the exported PDF
+const LINES_PER_PAGE: usize = 40;
+
+/// Width in characters that dot leaders pad each index line to
+const LINE_WIDTH: usize = 72;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NameIndexEntry {
+    pub name: String,
+    /// 1-based bundle page numbers, ascending
+    pub pages: Vec<usize>,
+}
+
+/// A file placed in the bundle, in bundle order
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameIndexSource {
+    pub path: String,
+    /// Stored page count; the PDF's own page count is used when unset
+    pub page_count: Option<usize>,
+}
+
+fn is_capitalised(word: &str) -> bool {
+    let mut chars = word.chars();
+    chars.next().is_some_and(char::is_uppercase)
+        && chars.clone().any(char::is_lowercase)
+        && chars.all(|c| c.is_alphabetic() || c == '\'' || c == '-')
+}
+
+/// Capitalised multi-word names in `text`, in order of appearance.
+/// `exclude` entries are matched case-insensitively against whole names and,
+/// for single words, also act as extra stopwords.
+pub fn extract_names(text: &str, exclude: &[String]) -> Vec<String> {
+    let is_stopword = |word: &str| {
+        STOPWORDS.contains(&word) || exclude.iter().any(|e| e.eq_ignore_ascii_case(word))
+    };
+
+    let mut names = Vec::new();
+    let mut run: Vec<&str> = Vec::new();
+    let mut finish = |run: &mut Vec<&str>| {
+        while run.first().is_some_and(|w| is_stopword(w)) {
+            run.remove(0);
+        }
+        while run.last().is_some_and(|w| is_stopword(w)) {
+            run.pop();
+        }
+        if run.len() >= 2 {
+            let name = run.join(" ");
+            if !exclude.iter().any(|e| e.eq_ignore_ascii_case(&name)) {
+                names.push(name);
+            }
+        }
+        run.clear();
+    };
+
+    for token in text.split_whitespace() {
+        let word = token.trim_start_matches(['(', '"', '\'']);
+        let stripped = word.trim_end_matches([',', '.', ';', ':', ')', '"', '\'']);
+        if is_capitalised(stripped) {
+            run.push(stripped);
+        } else {
+            finish(&mut run);
+        }
+        // Punctuation after a word ends the name it belongs to
+        if stripped.len() != word.len() {
+            finish(&mut run);
+        }
+    }
+    finish(&mut run);
+    names
+}
+
+/// Names found on each page of a PDF, indexed by 0-based page
+pub fn names_by_page(file_path: &str, exclude: &[String]) -> Result<Vec<Vec<String>>, String> {
+    let doc = lopdf::Document::load(file_path).map_err(|e| format!("Failed to load PDF: {}", e))?;
+    Ok(doc
+        .page_iter()
+        .map(|page_id| {
+            extract_page_text(&doc, page_id)
+                .map(|text| extract_names(&text, exclude))
+                .unwrap_or_default()
+        })
+        .collect())
+}
+
+/// Build an alphabetical index of names mentioned across a bundle.
+///
+/// Sources are laid out in order, each taking its page count, so every name
+/// maps to bundle page numbers. Files that can't be read contribute no names
+/// but still occupy their pages.
+pub fn build_name_index(sources: &[NameIndexSource], exclude: &[String]) -> Vec<NameIndexEntry> {
+    let mut index: BTreeMap<String, (String, BTreeSet<usize>)> = BTreeMap::new();
+    let mut offset = 0;
+
+    for source in sources {
+        let pages = names_by_page(&source.path, exclude).unwrap_or_default();
+        for (page_index, names) in pages.iter().enumerate() {
+            for name in names {
+                index
+                    .entry(name.to_lowercase())
+                    .or_insert_with(|| (name.clone(), BTreeSet::new()))
+                    .1
+                    .insert(offset + page_index + 1);
+            }
+        }
+        offset += source.page_count.unwrap_or(pages.len());
+    }
+
+    index
+        .into_values()
+        .map(|(name, pages)| NameIndexEntry {
+            name,
+            pages: pages.into_iter().collect(),
+        })
+        .collect()
+}
+
+fn index_line(entry: &NameIndexEntry) -> String {
+    let pages = entry
+        .pages
+        .iter()
+        .map(usize::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    let used = entry.name.chars().count() + pages.chars().count() + 2;
+    let leaders = ".".repeat(LINE_WIDTH.saturating_sub(used).max(3));
+    format!("{} {} {}", entry.name, leaders, pages)
+}
+
+/// Write the index as a PDF titled "INDEX OF NAMES", paginated as needed.
+/// Returns the number of pages written.
+pub fn write_name_index_pdf(
+    entries: &[NameIndexEntry],
+    output_path: &str,
+) -> Result<usize, String> {
+    let (mut doc, pages_id) = empty_document();
+    let media_box: Vec<Object> = A4_MEDIA_BOX.iter().map(|&v| v.into()).collect();
+    let (_, height) = media_box_size(&media_box);
+
+    let lines: Vec<String> = entries.iter().map(index_line).collect();
+    let chunks: Vec<&[String]> = if lines.is_empty() {
+        vec![&[]]
+    } else {
+        lines.chunks(LINES_PER_PAGE).collect()
+    };
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        let mut content = String::new();
+        if i == 0 {
+            content.push_str(&format!(
+                "BT /F1 16 Tf 72 {} Td (INDEX OF NAMES) Tj ET\n",
+                height - 72.0
+            ));
+        }
+        content.push_str(&format!("BT /F1 10 Tf 16 TL 72 {} Td\n", height - 108.0));
+        for line in chunk.iter() {
+            content.push_str(&format!("({}) Tj T*\n", encode_pdf_text(line)?));
+        }
+        content.push_str("ET\n");
+        insert_text_page(
+            &mut doc,
+            pages_id,
+            false,
+            media_box.clone(),
+            content.into_bytes(),
+        )?;
+    }
+
+    doc.save(output_path)
+        .map_err(|e| format!("Failed to save name index: {}", e))?;
+    Ok(chunks.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::fixtures::temp_pdf_path;
+
+    #[test]
+    fn test_extract_names() {
+        let text = "Dear Mr Tan Ah Kow, on 3 March we met Lim Holdings at The Ritz. \
+                    The High Court said so.";
+        assert_eq!(
+            extract_names(text, &[]),
+            vec!["Tan Ah Kow", "Lim Holdings", "High Court"]
+        );
+
+        let exclude = vec!["high court".to_string(), "Holdings".to_string()];
+        assert_eq!(extract_names(text, &exclude), vec!["Tan Ah Kow"]);
+    }
+
+    #[test]
+    fn test_write_name_index_pdf() {
+        let entries: Vec<NameIndexEntry> = (0..45)
+            .map(|i| NameIndexEntry {
+                name: format!("Party Number{}", i),
+                pages: vec![i + 1, i + 10],
+            })
+            .collect();
+        let path = temp_pdf_path("name-index");
+
+        let pages = write_name_index_pdf(&entries, path.to_str().unwrap()).unwrap();
+        let doc = lopdf::Document::load(&path).unwrap();
+        let first = *doc.get_pages().values().next().unwrap();
+        let text = extract_page_text(&doc, first).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(pages, 2);
+        assert!(text.starts_with("INDEX OF NAMES"));
+        assert!(text.contains("Party Number0 ."));
+        assert!(text.contains(" 1, 10"));
+    }
+}
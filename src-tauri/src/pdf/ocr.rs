@@ -0,0 +1,122 @@
+//! OCR of scanned pages
+//!
+//! Pages are rendered with PDFium and read by Tesseract, both only linked
+//! when the `ocr` feature is enabled. Default builds report OCR as
+//! unavailable and callers carry on with the native text layer.
+
+use super::error::PdfError;
+use super::load::load_document;
+
+/// Longest side of a page rendered for OCR, about 300 dpi for A4
+#[cfg(feature = "ocr")]
+const OCR_RENDER_PX: u32 = 3508;
+
+/// Text of every page in reading order, recovered by OCR
+pub fn extract_text_ocr(file_path: &str, password: Option<&str>) -> Result<String, PdfError> {
+    let page_count = load_document(file_path, password)?.get_pages().len();
+    let pages = (0..page_count)
+        .map(|page_index| ocr_page_text(file_path, password, page_index))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(pages.join("\n\n"))
+}
+
+/// Text of one page recovered by OCR
+#[cfg(feature = "ocr")]
+pub(super) fn ocr_page_text(
+    file_path: &str,
+    password: Option<&str>,
+    page_index: usize,
+) -> Result<String, PdfError> {
+    let png =
+        super::thumbnail::render_page_thumbnail(file_path, password, page_index, OCR_RENDER_PX)?;
+    let mut tesseract = leptess::LepTess::new(None, "eng")
+        .map_err(|e| PdfError::Unavailable(format!("Tesseract is not available: {}", e)))?;
+    tesseract
+        .set_image_from_mem(&png)
+        .map_err(|e| PdfError::Invalid(format!("Failed to read rendered page: {}", e)))?;
+    tesseract
+        .get_utf8_text()
+        .map(|text| text.trim().to_string())
+        .map_err(|e| PdfError::Invalid(format!("OCR produced invalid text: {}", e)))
+}
+
+#[cfg(not(feature = "ocr"))]
+pub(super) fn ocr_page_text(
+    _file_path: &str,
+    _password: Option<&str>,
+    _page_index: usize,
+) -> Result<String, PdfError> {
+    Err(PdfError::Unavailable(
+        "OCR is not available in this build".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::fixtures::temp_pdf_path;
+
+    #[cfg(not(feature = "ocr"))]
+    #[test]
+    fn test_ocr_unavailable_without_feature() {
+        use crate::pdf::fixtures::write_image_only_pdf;
+
+        let path = temp_pdf_path("ocr-disabled");
+        write_image_only_pdf(&path);
+
+        let result = extract_text_ocr(path.to_str().unwrap(), None);
+        std::fs::remove_file(&path).ok();
+
+        let error = result.unwrap_err();
+        assert_eq!(error.kind(), "unavailable");
+        assert_eq!(error.to_string(), "OCR is not available in this build");
+    }
+
+    #[cfg(feature = "ocr")]
+    #[test]
+    fn test_text_recovered_from_image_only_pdf() {
+        use crate::pdf::fixtures::{build_pdf, write_text_pdf};
+        use lopdf::{dictionary, Dictionary, Stream};
+
+        // "Scan" a text page: render it, then embed the pixels as the only content
+        let text_path = temp_pdf_path("ocr-source");
+        write_text_pdf(&text_path, &["LETTER OF DEMAND"]);
+        let png = super::super::thumbnail::render_page_thumbnail(
+            text_path.to_str().unwrap(),
+            None,
+            0,
+            OCR_RENDER_PX,
+        )
+        .unwrap();
+        std::fs::remove_file(&text_path).ok();
+        let scan = image::load_from_memory(&png).unwrap().to_luma8();
+
+        let mut doc = build_pdf(vec![(
+            b"q 595 0 0 842 0 0 cm /Im1 Do Q".to_vec(),
+            Dictionary::new(),
+        )]);
+        let image_id = doc.add_object(Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => scan.width() as i64,
+                "Height" => scan.height() as i64,
+                "ColorSpace" => "DeviceGray",
+                "BitsPerComponent" => 8,
+            },
+            scan.into_raw(),
+        ));
+        let page_id = doc.page_iter().next().unwrap();
+        doc.get_dictionary_mut(page_id).unwrap().set(
+            "Resources",
+            dictionary! { "XObject" => dictionary! { "Im1" => image_id } },
+        );
+        let path = temp_pdf_path("ocr-scan");
+        doc.save(&path).unwrap();
+
+        let text = extract_text_ocr(path.to_str().unwrap(), None);
+        std::fs::remove_file(&path).ok();
+
+        assert!(text.unwrap().contains("LETTER OF DEMAND"));
+    }
+}
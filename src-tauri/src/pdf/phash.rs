@@ -0,0 +1,197 @@
+//! Perceptual hashing of scanned pages for visual duplicate detection
+//!
+//! There is no page renderer here, so the hash is taken from the largest image
+//! painted on the page, which for scanned exhibits is the scan itself.
+
+use image::imageops::FilterType;
+use image::{DynamicImage, GrayImage, RgbImage};
+use lopdf::{Document, Object, Stream};
+
+/// Difference hash: 64 bits comparing horizontally adjacent pixels of a 9x8
+/// grayscale thumbnail. Robust to rescanning, scaling and small brightness shifts.
+fn difference_hash(image: &GrayImage) -> u64 {
+    let thumbnail = image::imageops::resize(image, 9, 8, FilterType::Triangle);
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let left = thumbnail.get_pixel(x, y)[0];
+            let right = thumbnail.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | u64::from(left < right);
+        }
+    }
+    hash
+}
+
+/// Decode an image XObject to grayscale. Supports JPEG and 8-bit
+/// gray/RGB/CMYK samples, either uncompressed or Flate/LZW-compressed.
+fn decode_image(stream: &Stream) -> Option<GrayImage> {
+    let filters = stream.filters().unwrap_or_default();
+    if filters.iter().any(|f| f == "DCTDecode") {
+        return image::load_from_memory(&stream.content)
+            .ok()
+            .map(|img| img.to_luma8());
+    }
+
+    let dict = &stream.dict;
+    let width = u32::try_from(dict.get(b"Width").and_then(Object::as_i64).ok()?).ok()?;
+    let height = u32::try_from(dict.get(b"Height").and_then(Object::as_i64).ok()?).ok()?;
+    if dict
+        .get(b"BitsPerComponent")
+        .and_then(Object::as_i64)
+        .ok()?
+        != 8
+    {
+        return None;
+    }
+
+    let samples = if filters.is_empty() {
+        stream.content.clone()
+    } else {
+        // lopdf refuses to decompress image streams directly
+        let mut plain = stream.clone();
+        plain.dict.remove(b"Subtype");
+        plain.decompressed_content().ok()?
+    };
+
+    let pixels = (width as usize) * (height as usize);
+    match dict.get(b"ColorSpace").and_then(Object::as_name).ok()? {
+        b"DeviceGray" => GrayImage::from_raw(width, height, samples.get(..pixels)?.to_vec()),
+        b"DeviceRGB" => RgbImage::from_raw(width, height, samples.get(..pixels * 3)?.to_vec())
+            .map(|rgb| DynamicImage::ImageRgb8(rgb).to_luma8()),
+        b"DeviceCMYK" => {
+            let gray = samples
+                .get(..pixels * 4)?
+                .chunks_exact(4)
+                .map(|cmyk| {
+                    let ink = 0.3 * f32::from(cmyk[0])
+                        + 0.59 * f32::from(cmyk[1])
+                        + 0.11 * f32::from(cmyk[2])
+                        + f32::from(cmyk[3]);
+                    255 - ink.min(255.0) as u8
+                })
+                .collect();
+            GrayImage::from_raw(width, height, gray)
+        }
+        _ => None,
+    }
+}
+
+/// Perceptual hash of the largest image on a page (0-based `page_index`)
+pub fn page_phash(file_path: &str, page_index: usize) -> Result<u64, String> {
+    let doc = Document::load(file_path).map_err(|e| format!("Failed to load PDF: {}", e))?;
+    let page_id = doc
+        .page_iter()
+        .nth(page_index)
+        .ok_or_else(|| format!("Page index {} is out of range", page_index))?;
+
+    let (inline, ids) = doc.get_page_resources(page_id);
+    let resources = inline
+        .into_iter()
+        .chain(ids.into_iter().filter_map(|id| doc.get_dictionary(id).ok()));
+
+    let mut largest: Option<(u64, &Stream)> = None;
+    for res in resources {
+        let Ok(xobjects) = res.get(b"XObject") else {
+            continue;
+        };
+        let xobjects = match xobjects {
+            Object::Reference(id) => doc.get_dictionary(*id).ok(),
+            other => other.as_dict().ok(),
+        };
+        for (_, xobject) in xobjects.into_iter().flat_map(|d| d.iter()) {
+            let Ok(id) = xobject.as_reference() else {
+                continue;
+            };
+            let Ok(Object::Stream(stream)) = doc.get_object(id) else {
+                continue;
+            };
+            if stream.dict.get(b"Subtype").and_then(Object::as_name).ok() != Some(b"Image") {
+                continue;
+            }
+            let dimension = |key: &[u8]| {
+                stream
+                    .dict
+                    .get(key)
+                    .and_then(Object::as_i64)
+                    .unwrap_or(0)
+                    .max(0) as u64
+            };
+            let area = dimension(b"Width") * dimension(b"Height");
+            if largest.is_none_or(|(best, _)| area > best) {
+                largest = Some((area, stream));
+            }
+        }
+    }
+
+    let (_, stream) = largest.ok_or("Page has no image to hash")?;
+    let image = decode_image(stream).ok_or("Unsupported image encoding")?;
+    Ok(difference_hash(&image))
+}
+
+/// Number of differing bits between two hashes
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::fixtures::{temp_pdf_path, write_scan_pdf, write_text_pdf};
+
+    /// A diagonal gradient with a dark block, like a letterhead
+    fn letter(x: u32, y: u32) -> u8 {
+        if (4..12).contains(&x) && (4..10).contains(&y) {
+            20
+        } else {
+            ((x * 5 + y * 3) % 256) as u8
+        }
+    }
+
+    #[test]
+    fn test_near_identical_scans_have_close_hashes() {
+        let original = temp_pdf_path("phash-a");
+        let rescan = temp_pdf_path("phash-b");
+        let different = temp_pdf_path("phash-c");
+        write_scan_pdf(&original, letter);
+        // Slightly brighter with a speck of noise
+        write_scan_pdf(&rescan, |x, y| {
+            if x == 20 && y == 20 {
+                255
+            } else {
+                letter(x, y).saturating_add(6)
+            }
+        });
+        write_scan_pdf(
+            &different,
+            |x, y| if (x / 4 + y / 4) % 2 == 0 { 0 } else { 255 },
+        );
+
+        let a = page_phash(original.to_str().unwrap(), 0).unwrap();
+        let b = page_phash(rescan.to_str().unwrap(), 0).unwrap();
+        let c = page_phash(different.to_str().unwrap(), 0).unwrap();
+        for path in [&original, &rescan, &different] {
+            std::fs::remove_file(path).ok();
+        }
+
+        assert!(
+            hamming_distance(a, b) <= 4,
+            "distance {}",
+            hamming_distance(a, b)
+        );
+        assert!(
+            hamming_distance(a, c) > 10,
+            "distance {}",
+            hamming_distance(a, c)
+        );
+    }
+
+    #[test]
+    fn test_text_page_has_no_phash() {
+        let path = temp_pdf_path("phash-text");
+        write_text_pdf(&path, &["No images here"]);
+        let result = page_phash(path.to_str().unwrap(), 0);
+        std::fs::remove_file(&path).ok();
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,356 @@
+//! Positioned text extraction for search highlighting
+//!
+//! Walks a page's content stream tracking the graphics and text matrices so
+//! each word gets an approximate bounding box. Glyph widths aren't read from
+//! font metrics; every character is assumed to be half an em wide.
+
+use lopdf::content::Operation;
+use lopdf::{Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+
+/// Average glyph advance as a fraction of the font size
+const AVERAGE_GLYPH_WIDTH: f32 = 0.5;
+
+/// TJ adjustments (thousandths of an em) at or below this are treated as word gaps
+const WORD_GAP_ADJUSTMENT: f32 = -200.0;
+
+/// A word and its approximate box in PDF user space (points, origin bottom-left).
+/// `x`/`y` are the baseline start; `height` is the rendered font size.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextSpan {
+    pub text: String,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Affine transform `[a b c d e f]`, applied to row vectors as in the PDF spec
+#[derive(Debug, Clone, Copy)]
+struct Matrix([f32; 6]);
+
+impl Matrix {
+    const IDENTITY: Matrix = Matrix([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+
+    fn translate(tx: f32, ty: f32) -> Matrix {
+        Matrix([1.0, 0.0, 0.0, 1.0, tx, ty])
+    }
+
+    /// `self × other`: apply `self` first, then `other`
+    fn then(self, other: Matrix) -> Matrix {
+        let [a, b, c, d, e, f] = self.0;
+        let [a2, b2, c2, d2, e2, f2] = other.0;
+        Matrix([
+            a * a2 + b * c2,
+            a * b2 + b * d2,
+            c * a2 + d * c2,
+            c * b2 + d * d2,
+            e * a2 + f * c2 + e2,
+            e * b2 + f * d2 + f2,
+        ])
+    }
+
+    fn apply(self, x: f32, y: f32) -> (f32, f32) {
+        let [a, b, c, d, e, f] = self.0;
+        (x * a + y * c + e, x * b + y * d + f)
+    }
+
+    fn vertical_scale(self) -> f32 {
+        let [_, _, c, d, _, _] = self.0;
+        (c * c + d * d).sqrt()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TextState {
+    font_size: f32,
+    leading: f32,
+    char_spacing: f32,
+    word_spacing: f32,
+    horizontal_scale: f32,
+    rise: f32,
+}
+
+impl Default for TextState {
+    fn default() -> Self {
+        TextState {
+            font_size: 0.0,
+            leading: 0.0,
+            char_spacing: 0.0,
+            word_spacing: 0.0,
+            horizontal_scale: 1.0,
+            rise: 0.0,
+        }
+    }
+}
+
+/// A word being accumulated across one or more shown strings
+struct PendingWord {
+    text: String,
+    start: (f32, f32),
+    end: (f32, f32),
+    height: f32,
+}
+
+struct PositionTracker {
+    ctm: Matrix,
+    saved_states: Vec<(Matrix, TextState)>,
+    state: TextState,
+    text_matrix: Matrix,
+    line_matrix: Matrix,
+    word: Option<PendingWord>,
+    spans: Vec<TextSpan>,
+}
+
+impl PositionTracker {
+    fn new() -> Self {
+        PositionTracker {
+            ctm: Matrix::IDENTITY,
+            saved_states: Vec::new(),
+            state: TextState::default(),
+            text_matrix: Matrix::IDENTITY,
+            line_matrix: Matrix::IDENTITY,
+            word: None,
+            spans: Vec::new(),
+        }
+    }
+
+    fn rendering_matrix(&self) -> Matrix {
+        self.text_matrix.then(self.ctm)
+    }
+
+    fn flush_word(&mut self) {
+        if let Some(word) = self.word.take() {
+            let (x0, y0) = word.start;
+            let (x1, y1) = word.end;
+            self.spans.push(TextSpan {
+                text: word.text,
+                x: x0.min(x1),
+                y: y0.min(y1),
+                width: ((x1 - x0).powi(2) + (y1 - y0).powi(2)).sqrt(),
+                height: word.height,
+            });
+        }
+    }
+
+    fn move_line(&mut self, tx: f32, ty: f32) {
+        self.flush_word();
+        self.line_matrix = Matrix::translate(tx, ty).then(self.line_matrix);
+        self.text_matrix = self.line_matrix;
+    }
+
+    /// Advance the text matrix horizontally by `tx` text-space units
+    fn advance(&mut self, tx: f32) {
+        self.text_matrix = Matrix::translate(tx, 0.0).then(self.text_matrix);
+    }
+
+    fn show_string(&mut self, bytes: &[u8]) {
+        let state = self.state;
+        for &byte in bytes {
+            // Simple fonts: one byte per glyph, read as Latin-1
+            let ch = char::from(byte);
+            let mut advance = AVERAGE_GLYPH_WIDTH * state.font_size + state.char_spacing;
+            if byte == b' ' {
+                advance += state.word_spacing;
+            }
+            let advance = advance * state.horizontal_scale;
+
+            if ch.is_whitespace() || ch.is_control() {
+                self.flush_word();
+            } else {
+                let matrix = self.rendering_matrix();
+                let start = matrix.apply(0.0, state.rise);
+                let end = matrix.apply(advance, state.rise);
+                match &mut self.word {
+                    Some(word) => {
+                        word.text.push(ch);
+                        word.end = end;
+                    }
+                    None => {
+                        self.word = Some(PendingWord {
+                            text: ch.to_string(),
+                            start,
+                            end,
+                            height: state.font_size * matrix.vertical_scale(),
+                        })
+                    }
+                }
+            }
+            self.advance(advance);
+        }
+    }
+
+    fn operation(&mut self, op: &Operation) {
+        let number = |i: usize| op.operands.get(i).and_then(|o| o.as_float().ok());
+
+        match op.operator.as_str() {
+            "q" => self.saved_states.push((self.ctm, self.state)),
+            "Q" => {
+                if let Some((ctm, state)) = self.saved_states.pop() {
+                    self.ctm = ctm;
+                    self.state = state;
+                }
+            }
+            "cm" => {
+                if let (Some(a), Some(b), Some(c), Some(d), Some(e), Some(f)) = (
+                    number(0),
+                    number(1),
+                    number(2),
+                    number(3),
+                    number(4),
+                    number(5),
+                ) {
+                    self.ctm = Matrix([a, b, c, d, e, f]).then(self.ctm);
+                }
+            }
+            "BT" => {
+                self.text_matrix = Matrix::IDENTITY;
+                self.line_matrix = Matrix::IDENTITY;
+            }
+            "ET" => self.flush_word(),
+            "Tf" => {
+                if let Some(size) = number(1) {
+                    self.state.font_size = size;
+                }
+            }
+            "TL" => self.state.leading = number(0).unwrap_or(self.state.leading),
+            "Tc" => self.state.char_spacing = number(0).unwrap_or(self.state.char_spacing),
+            "Tw" => self.state.word_spacing = number(0).unwrap_or(self.state.word_spacing),
+            "Ts" => self.state.rise = number(0).unwrap_or(self.state.rise),
+            "Tz" => {
+                if let Some(scale) = number(0) {
+                    self.state.horizontal_scale = scale / 100.0;
+                }
+            }
+            "Td" => {
+                if let (Some(tx), Some(ty)) = (number(0), number(1)) {
+                    self.move_line(tx, ty);
+                }
+            }
+            "TD" => {
+                if let (Some(tx), Some(ty)) = (number(0), number(1)) {
+                    self.state.leading = -ty;
+                    self.move_line(tx, ty);
+                }
+            }
+            "Tm" => {
+                if let (Some(a), Some(b), Some(c), Some(d), Some(e), Some(f)) = (
+                    number(0),
+                    number(1),
+                    number(2),
+                    number(3),
+                    number(4),
+                    number(5),
+                ) {
+                    self.flush_word();
+                    self.line_matrix = Matrix([a, b, c, d, e, f]);
+                    self.text_matrix = self.line_matrix;
+                }
+            }
+            "T*" => self.move_line(0.0, -self.state.leading),
+            "Tj" => {
+                if let Some(Object::String(bytes, _)) = op.operands.first() {
+                    self.show_string(bytes);
+                }
+            }
+            "'" => {
+                self.move_line(0.0, -self.state.leading);
+                if let Some(Object::String(bytes, _)) = op.operands.first() {
+                    self.show_string(bytes);
+                }
+            }
+            "\"" => {
+                self.state.word_spacing = number(0).unwrap_or(self.state.word_spacing);
+                self.state.char_spacing = number(1).unwrap_or(self.state.char_spacing);
+                self.move_line(0.0, -self.state.leading);
+                if let Some(Object::String(bytes, _)) = op.operands.get(2) {
+                    self.show_string(bytes);
+                }
+            }
+            "TJ" => {
+                if let Some(Object::Array(items)) = op.operands.first() {
+                    for item in items {
+                        match item {
+                            Object::String(bytes, _) => self.show_string(bytes),
+                            other => {
+                                if let Ok(adjustment) = other.as_float() {
+                                    if adjustment <= WORD_GAP_ADJUSTMENT {
+                                        self.flush_word();
+                                    }
+                                    self.advance(
+                                        -adjustment / 1000.0
+                                            * self.state.font_size
+                                            * self.state.horizontal_scale,
+                                    );
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Words on one page (0-based `page_index`) with approximate bounding boxes
+pub fn extract_text_with_positions(
+    file_path: &str,
+    page_index: usize,
+) -> Result<Vec<TextSpan>, String> {
+    let doc = Document::load(file_path).map_err(|e| format!("Failed to load PDF: {}", e))?;
+
+    let page_id = doc
+        .page_iter()
+        .nth(page_index)
+        .ok_or_else(|| format!("Page index {} is out of range", page_index))?;
+    page_text_spans(&doc, page_id)
+}
+
+/// Words on an already loaded page
+pub(super) fn page_text_spans(doc: &Document, page_id: ObjectId) -> Result<Vec<TextSpan>, String> {
+    let content = doc
+        .get_and_decode_page_content(page_id)
+        .map_err(|e| format!("Failed to read page content: {}", e))?;
+
+    let mut tracker = PositionTracker::new();
+    for op in &content.operations {
+        tracker.operation(op);
+    }
+    tracker.flush_word();
+    Ok(tracker.spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::fixtures::{temp_pdf_path, write_text_pdf};
+
+    #[test]
+    fn test_word_positions_on_fixture_page() {
+        let path = temp_pdf_path("positions");
+        write_text_pdf(&path, &["Cover", "Exhibit A\nInvoice 42"]);
+
+        let spans = extract_text_with_positions(path.to_str().unwrap(), 1).unwrap();
+        let out_of_range = extract_text_with_positions(path.to_str().unwrap(), 2);
+        std::fs::remove_file(&path).ok();
+
+        let words: Vec<&str> = spans.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(words, vec!["Exhibit", "A", "Invoice", "42"]);
+
+        // Fixture text starts at (72, 770) in 12pt with 14pt leading
+        let exhibit = &spans[0];
+        assert_eq!((exhibit.x, exhibit.y), (72.0, 770.0));
+        assert!(exhibit.width > 0.0);
+        assert_eq!(exhibit.height, 12.0);
+
+        let a = &spans[1];
+        assert!(a.x > exhibit.x + exhibit.width);
+        assert_eq!(a.y, 770.0);
+
+        let invoice = &spans[2];
+        assert_eq!((invoice.x, invoice.y), (72.0, 756.0));
+
+        assert!(out_of_range.is_err());
+    }
+}
@@ -0,0 +1,96 @@
+//! Scanned (image-only) PDF detection
+//!
+//! A page counts as scanned when it paints an image but carries almost no
+//! extractable text, so text heuristics on it would come back blank.
+
+use lopdf::{Document, Object, ObjectId};
+
+use super::text::extract_page_text;
+
+/// Pages with fewer non-whitespace characters than this count as textless
+const MIN_TEXT_CHARS: usize = 20;
+
+/// Whether `text` is too short to be a real text layer, e.g. only a stamp
+pub(super) fn has_little_text(text: &str) -> bool {
+    text.chars().filter(|c| !c.is_whitespace()).count() < MIN_TEXT_CHARS
+}
+
+fn page_has_image(doc: &Document, page_id: ObjectId) -> bool {
+    let (inline, ids) = doc.get_page_resources(page_id);
+    inline
+        .into_iter()
+        .chain(ids.into_iter().filter_map(|id| doc.get_dictionary(id).ok()))
+        .filter_map(|res| match res.get(b"XObject").ok()? {
+            Object::Reference(id) => doc.get_dictionary(*id).ok(),
+            other => other.as_dict().ok(),
+        })
+        .flat_map(|xobjects| xobjects.iter())
+        .filter_map(
+            |(_, xobject)| match doc.get_object(xobject.as_reference().ok()?) {
+                Ok(Object::Stream(stream)) => Some(&stream.dict),
+                _ => None,
+            },
+        )
+        .any(|dict| dict.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"Image"))
+}
+
+fn is_scanned_page(doc: &Document, page_id: ObjectId) -> bool {
+    let little_text = extract_page_text(doc, page_id)
+        .map(|text| has_little_text(&text))
+        .unwrap_or(true);
+    little_text && page_has_image(doc, page_id)
+}
+
+/// Whether at least half of the PDF's pages are images with little or no
+/// text. Pages are checked only until the answer is settled.
+pub(super) fn is_scanned_pdf(doc: &Document) -> bool {
+    let pages = doc.get_pages();
+    if pages.is_empty() {
+        return false;
+    }
+    let needed = pages.len().div_ceil(2);
+    let (mut scanned, mut text) = (0, 0);
+    for &page_id in pages.values() {
+        if is_scanned_page(doc, page_id) {
+            scanned += 1;
+        } else {
+            text += 1;
+        }
+        if scanned >= needed {
+            return true;
+        }
+        if text > pages.len() - needed {
+            return false;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::fixtures::{temp_pdf_path, write_image_only_pdf, write_text_pdf};
+    use crate::pdf::load::load_document;
+
+    #[test]
+    fn test_text_pdf_is_not_scanned() {
+        let path = temp_pdf_path("scanned-text");
+        write_text_pdf(&path, &["Letter of demand dated 15 January 2024"]);
+
+        let doc = load_document(path.to_str().unwrap(), None).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(!is_scanned_pdf(&doc));
+    }
+
+    #[test]
+    fn test_image_only_pdf_is_scanned() {
+        let path = temp_pdf_path("scanned-image");
+        write_image_only_pdf(&path);
+
+        let doc = load_document(path.to_str().unwrap(), None).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(is_scanned_pdf(&doc));
+    }
+}
@@ -0,0 +1,255 @@
+//! Text stamps drawn over existing pages
+//!
+//! The page's original content is wrapped in `q`/`Q` so any transform it
+//! leaves behind can't move the stamp, then the stamp is appended as a new
+//! content stream in the corner's stamp area.
+
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId, Stream};
+
+use super::collision::{page_media_box, stamp_region, StampCorner, STAMP_MARGIN};
+use super::endorsement::{encode_pdf_text, helvetica};
+
+/// Resource name for the stamp font, unlikely to clash with the page's own
+const STAMP_FONT: &str = "CPStamp";
+
+/// Stamp font size in points
+const STAMP_FONT_SIZE: f32 = 10.0;
+
+/// Rough Helvetica advance per character, as a fraction of the font size
+const CHAR_WIDTH: f32 = 0.6;
+
+/// Baseline-to-baseline distance of boxed stamp lines, in points
+const BOX_LEADING: f32 = 12.0;
+
+/// Space between a boxed stamp's border and its text, in points
+const BOX_PADDING: f32 = 6.0;
+
+/// The page's resource dictionary as an object id to edit, creating an inline
+/// copy of any inherited resources so the page stops depending on its parent
+fn page_resources_id(doc: &mut Document, page_id: ObjectId) -> Result<ObjectId, String> {
+    let page = doc
+        .get_dictionary(page_id)
+        .map_err(|e| format!("Failed to read page: {}", e))?;
+    if let Ok(id) = page.get(b"Resources").and_then(Object::as_reference) {
+        return Ok(id);
+    }
+
+    let resources = match page.get(b"Resources").and_then(Object::as_dict) {
+        Ok(resources) => resources.clone(),
+        Err(_) => inherited_resources(doc, page).unwrap_or_default(),
+    };
+    let id = doc.add_object(resources);
+    doc.get_dictionary_mut(page_id)
+        .map_err(|e| format!("Failed to read page: {}", e))?
+        .set("Resources", id);
+    Ok(id)
+}
+
+fn inherited_resources(doc: &Document, page: &Dictionary) -> Option<Dictionary> {
+    let mut node = doc
+        .get_dictionary(page.get(b"Parent").and_then(Object::as_reference).ok()?)
+        .ok()?;
+    loop {
+        match node.get(b"Resources") {
+            Ok(Object::Reference(id)) => return doc.get_dictionary(*id).ok().cloned(),
+            Ok(Object::Dictionary(resources)) => return Some(resources.clone()),
+            _ => {}
+        }
+        node = doc
+            .get_dictionary(node.get(b"Parent").and_then(Object::as_reference).ok()?)
+            .ok()?;
+    }
+}
+
+/// Register the stamp font in a page's resources
+fn add_stamp_font(doc: &mut Document, page_id: ObjectId) -> Result<(), String> {
+    let font_id = doc.add_object(helvetica());
+    let resources_id = page_resources_id(doc, page_id)?;
+    let fonts_ref = doc
+        .get_dictionary(resources_id)
+        .and_then(|resources| resources.get(b"Font"))
+        .and_then(Object::as_reference)
+        .ok();
+
+    let fonts = match fonts_ref {
+        Some(id) => doc.get_dictionary_mut(id),
+        None => {
+            let resources = doc
+                .get_dictionary_mut(resources_id)
+                .map_err(|e| format!("Failed to read page resources: {}", e))?;
+            if !matches!(resources.get(b"Font"), Ok(Object::Dictionary(_))) {
+                resources.set("Font", Dictionary::new());
+            }
+            resources.get_mut(b"Font").and_then(Object::as_dict_mut)
+        }
+    }
+    .map_err(|e| format!("Failed to read page fonts: {}", e))?;
+    fonts.set(STAMP_FONT, font_id);
+    Ok(())
+}
+
+/// Append `overlay` as the page's last content stream, after restoring the
+/// graphics state from before the original content
+fn append_overlay(doc: &mut Document, page_id: ObjectId, overlay: &str) -> Result<(), String> {
+    add_stamp_font(doc, page_id)?;
+
+    let save_id = doc.add_object(Stream::new(dictionary! {}, b"q\n".to_vec()));
+    let overlay_id = doc.add_object(Stream::new(
+        dictionary! {},
+        format!("Q\n{}", overlay).into_bytes(),
+    ));
+
+    let page = doc
+        .get_dictionary_mut(page_id)
+        .map_err(|e| format!("Failed to read page: {}", e))?;
+    let mut contents: Vec<Object> = vec![save_id.into()];
+    match page.get(b"Contents") {
+        Ok(Object::Array(existing)) => contents.extend(existing.iter().cloned()),
+        Ok(existing @ Object::Reference(_)) => contents.push(existing.clone()),
+        _ => {}
+    }
+    contents.push(overlay_id.into());
+    page.set("Contents", contents);
+    Ok(())
+}
+
+fn text_width(text: &str) -> f32 {
+    text.chars().count() as f32 * STAMP_FONT_SIZE * CHAR_WIDTH
+}
+
+/// Draw `text` in a corner of a page, above the page's existing content
+pub(super) fn stamp_page(
+    doc: &mut Document,
+    page_id: ObjectId,
+    corner: StampCorner,
+    text: &str,
+) -> Result<(), String> {
+    let media_box = page_media_box(doc, page_id).unwrap_or([0.0, 0.0, 595.0, 842.0]);
+    let [x0, y0, x1, y1] = stamp_region(media_box, corner);
+    let x = match corner {
+        StampCorner::TopLeft | StampCorner::BottomLeft => x0,
+        StampCorner::TopRight | StampCorner::BottomRight => (x1 - text_width(text)).max(x0),
+    };
+    let y = y0 + (y1 - y0 - STAMP_FONT_SIZE) / 2.0;
+
+    let overlay = format!(
+        "q BT /{} {} Tf {} {} Td ({}) Tj ET Q\n",
+        STAMP_FONT,
+        STAMP_FONT_SIZE,
+        x,
+        y,
+        encode_pdf_text(text)?
+    );
+    append_overlay(doc, page_id, &overlay)
+}
+
+/// Draw `lines` inside a bordered box in a corner of a page, sized to fit
+pub(super) fn stamp_page_box(
+    doc: &mut Document,
+    page_id: ObjectId,
+    corner: StampCorner,
+    lines: &[String],
+) -> Result<(), String> {
+    let [left, bottom, right, top] =
+        page_media_box(doc, page_id).unwrap_or([0.0, 0.0, 595.0, 842.0]);
+    let longest = lines
+        .iter()
+        .map(|line| text_width(line))
+        .fold(0.0, f32::max);
+    let width = longest + 2.0 * BOX_PADDING;
+    let height = lines.len() as f32 * BOX_LEADING + 2.0 * BOX_PADDING;
+    let x = match corner {
+        StampCorner::TopLeft | StampCorner::BottomLeft => left + STAMP_MARGIN,
+        StampCorner::TopRight | StampCorner::BottomRight => right - STAMP_MARGIN - width,
+    };
+    let y = match corner {
+        StampCorner::TopLeft | StampCorner::TopRight => top - STAMP_MARGIN - height,
+        StampCorner::BottomLeft | StampCorner::BottomRight => bottom + STAMP_MARGIN,
+    };
+
+    let mut overlay = format!("q 0.75 w {} {} {} {} re S Q\n", x, y, width, height);
+    overlay.push_str(&format!(
+        "q BT /{} {} Tf {} TL {} {} Td\n",
+        STAMP_FONT,
+        STAMP_FONT_SIZE,
+        BOX_LEADING,
+        x + BOX_PADDING,
+        y + height - BOX_PADDING - STAMP_FONT_SIZE
+    ));
+    for line in lines {
+        overlay.push_str(&format!("({}) Tj T*\n", encode_pdf_text(line)?));
+    }
+    overlay.push_str("ET Q\n");
+    append_overlay(doc, page_id, &overlay)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::fixtures::build_pdf;
+    use crate::pdf::text::extract_page_text;
+
+    #[test]
+    fn test_stamp_survives_unbalanced_transform() {
+        // The page scales everything by 5 and never restores the state
+        let content = b"5 0 0 5 0 0 cm BT /F1 12 Tf 10 150 Td (Body) Tj ET".to_vec();
+        let resources = dictionary! {
+            "Font" => dictionary! {
+                "F1" => dictionary! { "Type" => "Font", "Subtype" => "Type1", "BaseFont" => "Helvetica" },
+            },
+        };
+        let mut doc = build_pdf(vec![(content, resources)]);
+        let page_id = *doc.get_pages().values().next().unwrap();
+
+        stamp_page(&mut doc, page_id, StampCorner::BottomRight, "TAB 1").unwrap();
+
+        let bytes = doc.get_page_content(page_id).unwrap();
+        let content = String::from_utf8_lossy(&bytes);
+        assert!(content.starts_with("q\n"));
+        assert!(content.contains("Q\nq BT /CPStamp 10 Tf"));
+        assert_eq!(extract_page_text(&doc, page_id).unwrap(), "Body\nTAB 1");
+    }
+
+    fn blank_page() -> (Document, ObjectId) {
+        let doc = build_pdf(vec![(b"".to_vec(), dictionary! {})]);
+        let page_id = *doc.get_pages().values().next().unwrap();
+        (doc, page_id)
+    }
+
+    #[test]
+    fn test_stamp_box_encodes_accented_names_as_win_ansi() {
+        let (mut doc, page_id) = blank_page();
+        let lines = vec!["Affidavit of Zoë (2nd)".to_string()];
+
+        stamp_page_box(&mut doc, page_id, StampCorner::TopRight, &lines).unwrap();
+
+        let bytes = doc.get_page_content(page_id).unwrap();
+        let content = String::from_utf8(bytes).unwrap();
+        assert!(content.contains("(Affidavit of Zo\\353 \\(2nd\\)) Tj"));
+
+        let resources_id = page_resources_id(&mut doc, page_id).unwrap();
+        let resources = doc.get_dictionary(resources_id).unwrap();
+        let fonts = resources.get(b"Font").unwrap().as_dict().unwrap();
+        let font_id = fonts
+            .get(STAMP_FONT.as_bytes())
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        let font = doc.get_dictionary(font_id).unwrap();
+        assert_eq!(
+            font.get(b"Encoding").unwrap().as_name().unwrap(),
+            b"WinAnsiEncoding"
+        );
+    }
+
+    #[test]
+    fn test_stamp_box_rejects_characters_outside_win_ansi() {
+        let (mut doc, page_id) = blank_page();
+        let lines = vec!["Affidavit of Nguyễn".to_string()];
+
+        let err = stamp_page_box(&mut doc, page_id, StampCorner::TopRight, &lines).unwrap_err();
+
+        assert!(err.contains("'ễ'"), "{}", err);
+        assert!(doc.get_page_content(page_id).unwrap().is_empty());
+    }
+}
@@ -1,79 +1,173 @@
 //! Text extraction from PDF content
 
-use lopdf::{Document, Object};
+use std::iter::Peekable;
+use std::str::Chars;
+
+use super::error::PdfError;
 
 /// Extract text content from a specific page of a PDF
-pub fn extract_page_text(doc: &Document, page_id: lopdf::ObjectId) -> Result<String, String> {
-    let mut text = String::new();
+pub fn extract_page_text(
+    doc: &lopdf::Document,
+    page_id: lopdf::ObjectId,
+) -> Result<String, String> {
+    // get_page_content resolves both a single Contents stream and an array of them
+    let content_bytes = doc
+        .get_page_content(page_id)
+        .map_err(|e| format!("Failed to read page content: {}", e))?;
 
-    // Get the page dictionary
-    if let Ok(Object::Dictionary(page_dict)) = doc.get_object(page_id) {
-        // Look for Contents stream(s)
-        if let Ok(contents) = page_dict.get(b"Contents") {
-            match contents {
-                Object::Reference(stream_id) => {
-                    if let Ok(content_bytes) = doc.get_page_content(*stream_id) {
-                        text.push_str(&extract_text_from_content(&content_bytes));
-                    }
-                }
-                Object::Array(arr) => {
-                    for item in arr {
-                        if let Object::Reference(stream_id) = item {
-                            if let Ok(content_bytes) = doc.get_page_content(*stream_id) {
-                                text.push_str(&extract_text_from_content(&content_bytes));
-                            }
-                        }
-                    }
+    Ok(extract_text_from_content(&content_bytes))
+}
+
+/// Decode the escape sequence following a `\` inside a literal string.
+/// Octal codes are read as single bytes (Latin-1); a backslash before a
+/// line break continues the string and produces nothing.
+fn read_escape(chars: &mut Peekable<Chars>) -> Option<char> {
+    let ch = chars.next()?;
+    match ch {
+        'n' => Some('\n'),
+        'r' => Some('\r'),
+        't' => Some('\t'),
+        'b' => Some('\u{8}'),
+        'f' => Some('\u{c}'),
+        '0'..='7' => {
+            let mut code = ch.to_digit(8)?;
+            for _ in 0..2 {
+                match chars.next_if(|c| c.is_digit(8)) {
+                    Some(digit) => code = code * 8 + digit.to_digit(8)?,
+                    None => break,
                 }
-                _ => {}
             }
+            Some(char::from((code & 0xff) as u8))
+        }
+        '\r' => {
+            chars.next_if_eq(&'\n');
+            None
+        }
+        '\n' => None,
+        // \(, \), \\ and any unknown escape yield the character itself
+        other => Some(other),
+    }
+}
+
+/// Read a literal string body after its opening `(`, honouring nested parentheses
+fn read_literal_string(chars: &mut Peekable<Chars>) -> String {
+    let mut current_text = String::new();
+    let mut paren_depth = 1;
+
+    while let Some(ch) = chars.next() {
+        if ch == '\\' {
+            if let Some(unescaped) = read_escape(chars) {
+                current_text.push(unescaped);
+            }
+            continue;
+        }
+        if ch == '(' {
+            paren_depth += 1;
+        } else if ch == ')' {
+            paren_depth -= 1;
+            if paren_depth == 0 {
+                break;
+            }
+        }
+        current_text.push(ch);
+    }
+
+    current_text
+}
+
+/// Read a hex string body after its opening `<`, decoding byte pairs as
+/// Latin-1. A trailing odd digit is padded with 0, per the PDF spec.
+fn read_hex_string(chars: &mut Peekable<Chars>) -> String {
+    let mut digits = Vec::new();
+    for ch in chars.by_ref() {
+        if ch == '>' {
+            break;
+        }
+        if let Some(digit) = ch.to_digit(16) {
+            digits.push(digit as u8);
         }
     }
+    if digits.len() % 2 == 1 {
+        digits.push(0);
+    }
 
-    Ok(text)
+    digits
+        .chunks(2)
+        .map(|pair| char::from(pair[0] << 4 | pair[1]))
+        .collect()
 }
 
-/// Extract visible text from PDF content stream bytes
+/// Operators that move to a new line of text
+const LINE_OPERATORS: &[&str] = &["Td", "TD", "Tm", "T*", "'", "\""];
+
+/// Extract visible text from PDF content stream bytes, one line of text per
+/// line. Spaces within a line are collapsed and blank lines dropped.
 pub fn extract_text_from_content(content: &[u8]) -> String {
     let mut text = String::new();
     let content_str = String::from_utf8_lossy(content);
+    let mut chars = content_str.chars().peekable();
 
-    // Simple text extraction: look for text between () in Tj and TJ operators
-    let mut in_text = false;
-    let mut current_text = String::new();
-    let mut paren_depth = 0;
+    // String operands seen since the last operator. A TJ array contributes
+    // several fragments (with numeric kerning in between) that form one run.
+    let mut fragments: Vec<String> = Vec::new();
 
-    for ch in content_str.chars() {
-        if ch == '(' && !in_text {
-            in_text = true;
-            paren_depth = 1;
-        } else if ch == '(' && in_text {
-            paren_depth += 1;
-            current_text.push(ch);
-        } else if ch == ')' && in_text {
-            paren_depth -= 1;
-            if paren_depth == 0 {
-                in_text = false;
-                text.push_str(&current_text);
-                text.push(' ');
-                current_text.clear();
-            } else {
-                current_text.push(ch);
+    while let Some(ch) = chars.next() {
+        match ch {
+            '(' => fragments.push(read_literal_string(&mut chars)),
+            '%' => {
+                // Comment runs to end of line
+                for next in chars.by_ref() {
+                    if next == '\n' || next == '\r' {
+                        break;
+                    }
+                }
+            }
+            '/' => {
+                // Skip names so they aren't mistaken for operators
+                while chars
+                    .next_if(|c| !c.is_whitespace() && !"()<>[]{}/%".contains(*c))
+                    .is_some()
+                {}
+            }
+            '<' if chars.peek() != Some(&'<') => fragments.push(read_hex_string(&mut chars)),
+            c if c.is_ascii_alphabetic() || c == '\'' || c == '"' => {
+                let mut operator = String::from(c);
+                while let Some(next) = chars.next_if(|c| c.is_ascii_alphabetic() || *c == '*') {
+                    operator.push(next);
+                }
+                if LINE_OPERATORS.contains(&operator.as_str()) {
+                    text.push('\n');
+                }
+                if matches!(operator.as_str(), "Tj" | "TJ" | "'" | "\"") {
+                    // Line breaks inside a string are just spacing
+                    let run = fragments.concat();
+                    text.extend(run.chars().map(|c| if c.is_whitespace() { ' ' } else { c }));
+                    text.push(' ');
+                }
+                fragments.clear();
             }
-        } else if in_text {
-            current_text.push(ch);
+            _ => {}
         }
     }
 
-    // Clean up: normalize whitespace
-    text.split_whitespace().collect::<Vec<_>>().join(" ")
+    // Clean up: normalize whitespace within each line
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
-/// Extract the first N characters of text from a PDF (for preview/description)
-pub fn extract_first_page_text(file_path: &str, max_chars: usize) -> Result<String, String> {
-    let doc =
-        Document::load(file_path).map_err(|e| format!("Failed to load PDF: {}", e))?;
+/// Truncate to at most `max_chars` characters, appending "..." when shortened
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((byte_index, _)) => format!("{}...", &text[..byte_index]),
+        None => text.to_string(),
+    }
+}
 
+/// Extract the first N characters of a document's first page (for preview/description)
+pub(super) fn first_page_text(doc: &lopdf::Document, max_chars: usize) -> Result<String, PdfError> {
     let pages = doc.get_pages();
     if pages.is_empty() {
         return Ok(String::new());
@@ -81,14 +175,91 @@ pub fn extract_first_page_text(file_path: &str, max_chars: usize) -> Result<Stri
 
     // Get first page ID
     if let Some((_, page_id)) = pages.iter().next() {
-        let text = extract_page_text(&doc, *page_id)?;
-        if text.len() > max_chars {
-            Ok(format!("{}...", &text[..max_chars]))
-        } else {
-            Ok(text)
-        }
+        let text = extract_page_text(doc, *page_id).map_err(PdfError::Invalid)?;
+        Ok(truncate_chars(&text, max_chars))
     } else {
         Ok(String::new())
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_chars_multibyte_boundary() {
+        // 'é' and '—' are multi-byte, so byte index 5 falls inside a character
+        let text = "Café — Tan Ah Kow";
+        assert_eq!(truncate_chars(text, 4), "Café...");
+        assert_eq!(truncate_chars(text, 6), "Café —...");
+        assert_eq!(truncate_chars(text, 100), text);
+        assert_eq!(truncate_chars("Zoë", 3), "Zoë");
+    }
+
+    #[test]
+    fn test_extract_text_tj_array() {
+        let content = b"BT /F1 12 Tf 72 770 Td [(Hel) -250 (lo)] TJ ( world) Tj ET";
+        assert_eq!(extract_text_from_content(content), "Hello world");
+    }
+
+    #[test]
+    fn test_extract_text_escaped_parentheses() {
+        let content = br"BT (Smith \(Pte\) Ltd) Tj ET";
+        assert_eq!(extract_text_from_content(content), "Smith (Pte) Ltd");
+    }
+
+    #[test]
+    fn test_extract_text_escaped_backslash() {
+        let content = br"BT (C:\\Bundles) Tj ET";
+        assert_eq!(extract_text_from_content(content), r"C:\Bundles");
+    }
+
+    #[test]
+    fn test_extract_text_whitespace_escapes() {
+        let content = br"BT (Page\n1\r2\t3) Tj ET";
+        assert_eq!(extract_text_from_content(content), "Page 1 2 3");
+
+        let mut chars = "n".chars().peekable();
+        assert_eq!(read_escape(&mut chars), Some('\n'));
+        let mut chars = "r".chars().peekable();
+        assert_eq!(read_escape(&mut chars), Some('\r'));
+        let mut chars = "t".chars().peekable();
+        assert_eq!(read_escape(&mut chars), Some('\t'));
+    }
+
+    #[test]
+    fn test_extract_text_octal_escapes() {
+        let content = br"BT (\251 2024 Tan \050SG\051) Tj ET";
+        assert_eq!(extract_text_from_content(content), "© 2024 Tan (SG)");
+    }
+
+    #[test]
+    fn test_extract_text_hex_strings() {
+        let content = b"BT <48656C6C6F> Tj [<576F> -120 <726c64>] TJ ET";
+        assert_eq!(extract_text_from_content(content), "Hello World");
+
+        // Odd-length hex is padded with a trailing zero: <41 42 5> -> "AB" + 0x50 'P'
+        let content = b"BT <4142 5> Tj ET";
+        assert_eq!(extract_text_from_content(content), "ABP");
+    }
+
+    #[test]
+    fn test_extract_text_tj_literal() {
+        let content = b"BT /F1 12 Tf 72 770 Td (Affidavit of Tan) Tj T* (Exhibit A) Tj ET";
+        assert_eq!(
+            extract_text_from_content(content),
+            "Affidavit of Tan\nExhibit A"
+        );
+    }
+
+    #[test]
+    fn test_extract_text_keeps_line_breaks() {
+        let content = b"BT 72 770 Td (Between) Tj 0 -14 Td (Tan  Ah Kow) Tj \
+                        0 -14 TD (... Plaintiff) Tj 14 TL (And) ' ET \
+                        BT 1 0 0 1 72 700 Tm [(Lim B) -20 (ee Hoon)] TJ ET";
+        assert_eq!(
+            extract_text_from_content(content),
+            "Between\nTan Ah Kow\n... Plaintiff\nAnd\nLim Bee Hoon"
+        );
+    }
+}
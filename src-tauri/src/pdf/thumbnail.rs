@@ -0,0 +1,124 @@
+//! Page thumbnails for the document repository
+//!
+//! Rendering needs PDFium, which is only linked when the `thumbnails`
+//! feature is enabled. The library is looked up next to the executable
+//! first, then on the system library path.
+
+use super::error::PdfError;
+use super::load::load_document;
+
+/// Render one page to PNG, scaled to fit within `max_px` on both sides
+pub fn render_page_thumbnail(
+    file_path: &str,
+    password: Option<&str>,
+    page_index: usize,
+    max_px: u32,
+) -> Result<Vec<u8>, PdfError> {
+    let page_count = load_document(file_path, password)?.get_pages().len();
+    if page_index >= page_count {
+        return Err(PdfError::Invalid(format!(
+            "Page index {} is out of range: the PDF has {} pages",
+            page_index, page_count
+        )));
+    }
+    if max_px == 0 {
+        return Err(PdfError::Invalid(
+            "Thumbnail size must be at least 1 pixel".to_string(),
+        ));
+    }
+    render_png(file_path, password, page_index, max_px)
+}
+
+#[cfg(feature = "thumbnails")]
+fn render_png(
+    file_path: &str,
+    password: Option<&str>,
+    page_index: usize,
+    max_px: u32,
+) -> Result<Vec<u8>, PdfError> {
+    use pdfium_render::prelude::*;
+
+    let bindings = Pdfium::bind_to_library(Pdfium::pdfium_platform_library_name_at_path("./"))
+        .or_else(|_| Pdfium::bind_to_system_library())
+        .map_err(|e| PdfError::Unavailable(format!("PDFium is not available: {}", e)))?;
+    let pdfium = Pdfium::new(bindings);
+
+    let document = pdfium
+        .load_pdf_from_file(file_path, password)
+        .map_err(|e| PdfError::Invalid(format!("Failed to load PDF: {}", e)))?;
+    let page = document
+        .pages()
+        .get(page_index as PdfPageIndex)
+        .map_err(|e| PdfError::Invalid(format!("Failed to load page: {}", e)))?;
+    let config = PdfRenderConfig::new()
+        .set_target_width(max_px as Pixels)
+        .set_maximum_height(max_px as Pixels);
+    let image = page
+        .render_with_config(&config)
+        .map_err(|e| PdfError::Invalid(format!("Failed to render page: {}", e)))?
+        .as_image();
+
+    let mut png = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| PdfError::Invalid(format!("Failed to encode thumbnail: {}", e)))?;
+    Ok(png)
+}
+
+#[cfg(not(feature = "thumbnails"))]
+fn render_png(
+    _file_path: &str,
+    _password: Option<&str>,
+    _page_index: usize,
+    _max_px: u32,
+) -> Result<Vec<u8>, PdfError> {
+    Err(PdfError::Unavailable(
+        "Thumbnails are not available in this build".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::fixtures::{temp_pdf_path, write_encrypted_pdf, write_text_pdf};
+
+    #[test]
+    fn test_out_of_range_page() {
+        let path = temp_pdf_path("thumb-range");
+        write_text_pdf(&path, &["Only page"]);
+
+        let result = render_page_thumbnail(path.to_str().unwrap(), None, 1, 200);
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Page index 1 is out of range: the PDF has 1 pages"
+        );
+    }
+
+    #[test]
+    fn test_encrypted_pdf_needs_password() {
+        let path = temp_pdf_path("thumb-encrypted");
+        write_encrypted_pdf(&path, "s3cret");
+
+        let result = render_page_thumbnail(path.to_str().unwrap(), None, 0, 200);
+        std::fs::remove_file(&path).ok();
+
+        assert!(matches!(result, Err(PdfError::Encrypted)));
+    }
+
+    #[cfg(feature = "thumbnails")]
+    #[test]
+    fn test_first_page_renders_to_png() {
+        let path = temp_pdf_path("thumb");
+        write_text_pdf(&path, &["Page one", "Page two"]);
+
+        let png = render_page_thumbnail(path.to_str().unwrap(), None, 0, 200);
+        std::fs::remove_file(&path).ok();
+
+        let png = png.unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        let image = image::load_from_memory(&png).unwrap();
+        assert!(image.width() <= 200 && image.height() <= 200);
+    }
+}
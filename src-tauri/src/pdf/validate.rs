@@ -0,0 +1,193 @@
+//! Pre-import validation of PDF files
+
+use lopdf::Document;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+/// How far into a file the `%PDF-` marker may appear; readers tolerate junk
+/// such as a BOM or mail headers before it
+const HEADER_SEARCH_BYTES: usize = 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PdfValidation {
+    pub path: String,
+    pub valid: bool,
+    pub page_count: usize,
+    pub encrypted: bool,
+    pub error: Option<String>,
+}
+
+/// Cheap check that a file exists, is non-empty and starts like a PDF,
+/// so renamed Word documents fail with a clear message before parsing
+pub fn check_pdf_header(file_path: &str) -> Result<(), String> {
+    let file = std::fs::File::open(file_path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => "File not found".to_string(),
+        _ => format!("Failed to open file: {}", e),
+    })?;
+
+    let mut head = Vec::with_capacity(HEADER_SEARCH_BYTES);
+    file.take(HEADER_SEARCH_BYTES as u64)
+        .read_to_end(&mut head)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    if head.is_empty() {
+        return Err("File is empty".to_string());
+    }
+    if !head.windows(5).any(|w| w == b"%PDF-") {
+        return Err("Not a PDF file (no %PDF- header)".to_string());
+    }
+    Ok(())
+}
+
+/// Check that a file opens as a PDF with at least one page and no encryption
+pub fn validate_pdf(file_path: &str) -> PdfValidation {
+    let mut result = PdfValidation {
+        path: file_path.to_string(),
+        valid: false,
+        page_count: 0,
+        encrypted: false,
+        error: None,
+    };
+
+    if let Err(e) = check_pdf_header(file_path) {
+        result.error = Some(e);
+        return result;
+    }
+
+    let doc = match Document::load(file_path) {
+        Ok(doc) => doc,
+        Err(e) => {
+            result.error = Some(format!("Not a valid PDF: {}", e));
+            return result;
+        }
+    };
+
+    result.page_count = doc.get_pages().len();
+    result.encrypted = doc.is_encrypted();
+    result.error = if result.encrypted {
+        Some("PDF is encrypted".to_string())
+    } else if result.page_count == 0 {
+        Some("PDF has no pages".to_string())
+    } else {
+        None
+    };
+    result.valid = result.error.is_none();
+    result
+}
+
+fn is_pdf(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("pdf"))
+}
+
+/// Validate every PDF directly inside `folder_path`, spreading the work across threads.
+/// Results are sorted by path.
+pub fn validate_folder(folder_path: &str) -> Result<Vec<PdfValidation>, String> {
+    let entries =
+        std::fs::read_dir(folder_path).map_err(|e| format!("Failed to read folder: {}", e))?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| is_pdf(path))
+        .collect();
+    paths.sort();
+
+    if paths.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let workers = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(paths.len());
+    let chunk_size = paths.len().div_ceil(workers);
+
+    let results = thread::scope(|scope| {
+        let handles: Vec<_> = paths
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|path| validate_pdf(&path.to_string_lossy()))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap_or_default())
+            .collect()
+    });
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::fixtures::write_text_pdf;
+
+    #[test]
+    fn test_validate_folder_reports_each_pdf() {
+        let folder =
+            std::env::temp_dir().join(format!("casepilot-validate-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&folder).unwrap();
+        write_text_pdf(&folder.join("a-valid.pdf"), &["Page one", "Page two"]);
+        std::fs::write(folder.join("b-corrupt.PDF"), b"%PDF-1.4\nnot really a pdf").unwrap();
+        std::fs::write(folder.join("notes.txt"), b"ignored").unwrap();
+
+        let results = validate_folder(folder.to_str().unwrap()).unwrap();
+        std::fs::remove_dir_all(&folder).ok();
+
+        assert_eq!(results.len(), 2);
+
+        let valid = &results[0];
+        assert!(valid.path.ends_with("a-valid.pdf"));
+        assert!(valid.valid);
+        assert_eq!(valid.page_count, 2);
+        assert!(!valid.encrypted);
+        assert_eq!(valid.error, None);
+
+        let corrupt = &results[1];
+        assert!(corrupt.path.ends_with("b-corrupt.PDF"));
+        assert!(!corrupt.valid);
+        assert_eq!(corrupt.page_count, 0);
+        assert!(corrupt.error.is_some());
+    }
+
+    #[test]
+    fn test_header_check_rejects_non_pdfs() {
+        let folder =
+            std::env::temp_dir().join(format!("casepilot-header-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&folder).unwrap();
+        let word = folder.join("letter.pdf");
+        let empty = folder.join("empty.pdf");
+        // A .docx is a zip archive
+        std::fs::write(&word, b"PK\x03\x04\x14\x00\x06\x00word/document.xml").unwrap();
+        std::fs::write(&empty, b"").unwrap();
+
+        let word_result = validate_pdf(word.to_str().unwrap());
+        let empty_result = validate_pdf(empty.to_str().unwrap());
+        let missing = check_pdf_header(folder.join("gone.pdf").to_str().unwrap());
+        std::fs::remove_dir_all(&folder).ok();
+
+        assert!(!word_result.valid);
+        assert_eq!(
+            word_result.error.as_deref(),
+            Some("Not a PDF file (no %PDF- header)")
+        );
+        assert_eq!(empty_result.error.as_deref(), Some("File is empty"));
+        assert_eq!(missing, Err("File not found".to_string()));
+    }
+
+    #[test]
+    fn test_validate_folder_missing() {
+        assert!(validate_folder("/non/existent/folder").is_err());
+    }
+}
@@ -5,13 +5,15 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
+import { errorMessage } from "../lib/errors";
 import type { Case, Document } from "./types";
 
 export async function listCases(): Promise<Case[]> {
   try {
-    return await invoke<Case[]>("list_cases");
+    const page = await invoke<{ cases: Case[]; total: number }>("list_cases");
+    return page.cases;
   } catch (e) {
-    console.error("[API] Failed to list cases:", e);
+    console.error("[API] Failed to list cases:", errorMessage(e));
     return [];
   }
 }
@@ -26,7 +28,7 @@ export async function createCase(
       request: { name, case_type: caseType, content_json: contentJson },
     });
   } catch (e) {
-    console.error("[API] Failed to create case:", e);
+    console.error("[API] Failed to create case:", errorMessage(e));
     return null;
   }
 }
@@ -36,7 +38,7 @@ export async function deleteCase(id: string): Promise<boolean> {
     await invoke("delete_case", { id });
     return true;
   } catch (e) {
-    console.error("[API] Failed to delete case:", e);
+    console.error("[API] Failed to delete case:", errorMessage(e));
     return false;
   }
 }
@@ -45,7 +47,7 @@ export async function listDocuments(caseId: string): Promise<Document[]> {
   try {
     return await invoke<Document[]>("list_documents", { caseId });
   } catch (e) {
-    console.error("[API] Failed to list documents:", e);
+    console.error("[API] Failed to list documents:", errorMessage(e));
     return [];
   }
 }
@@ -59,7 +61,7 @@ export async function createDocument(
       request: { case_id: caseId, name },
     });
   } catch (e) {
-    console.error("[API] Failed to create document:", e);
+    console.error("[API] Failed to create document:", errorMessage(e));
     return null;
   }
 }
@@ -68,7 +70,7 @@ export async function loadDocument(id: string): Promise<Document | null> {
   try {
     return await invoke<Document>("load_document", { id });
   } catch (e) {
-    console.error("[API] Failed to load document:", e);
+    console.error("[API] Failed to load document:", errorMessage(e));
     return null;
   }
 }
@@ -82,7 +84,7 @@ export async function saveDocument(
       request: { id, content },
     });
   } catch (e) {
-    console.error("[API] Failed to save document:", e);
+    console.error("[API] Failed to save document:", errorMessage(e));
     return null;
   }
 }
@@ -92,7 +94,7 @@ export async function deleteDocument(id: string): Promise<boolean> {
     await invoke("delete_document", { id });
     return true;
   } catch (e) {
-    console.error("[API] Failed to delete document:", e);
+    console.error("[API] Failed to delete document:", errorMessage(e));
     return false;
   }
 }
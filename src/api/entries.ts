@@ -5,13 +5,14 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
+import { errorMessage } from "../lib/errors";
 import type { ArtifactEntry, CompileResult, TOCEntry } from "./types";
 
 export async function listEntries(caseId: string): Promise<ArtifactEntry[]> {
   try {
     return await invoke<ArtifactEntry[]>("list_entries", { caseId });
   } catch (e) {
-    console.error("[API] Failed to list entries:", e);
+    console.error("[API] Failed to list entries:", errorMessage(e));
     return [];
   }
 }
@@ -36,7 +37,7 @@ export async function createEntry(
       },
     });
   } catch (e) {
-    console.error("[API] Failed to create entry:", e);
+    console.error("[API] Failed to create entry:", errorMessage(e));
     return null;
   }
 }
@@ -57,7 +58,7 @@ export async function updateEntry(
       },
     });
   } catch (e) {
-    console.error("[API] Failed to update entry:", e);
+    console.error("[API] Failed to update entry:", errorMessage(e));
     return null;
   }
 }
@@ -67,7 +68,7 @@ export async function deleteEntry(id: string): Promise<boolean> {
     await invoke("delete_entry", { id });
     return true;
   } catch (e) {
-    console.error("[API] Failed to delete entry:", e);
+    console.error("[API] Failed to delete entry:", errorMessage(e));
     return false;
   }
 }
@@ -84,7 +85,7 @@ export async function reorderEntries(
       },
     });
   } catch (e) {
-    console.error("[API] Failed to reorder entries:", e);
+    console.error("[API] Failed to reorder entries:", errorMessage(e));
     return [];
   }
 }
@@ -103,7 +104,7 @@ export async function compileBundle(
       },
     });
   } catch (e) {
-    console.error("[API] Failed to compile bundle:", e);
+    console.error("[API] Failed to compile bundle:", errorMessage(e));
     return null;
   }
 }
@@ -114,7 +115,7 @@ export async function previewToc(caseId: string): Promise<TOCEntry[]> {
       request: { case_id: caseId },
     });
   } catch (e) {
-    console.error("[API] Failed to preview TOC:", e);
+    console.error("[API] Failed to preview TOC:", errorMessage(e));
     return [];
   }
 }
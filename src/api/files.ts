@@ -5,13 +5,14 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
+import { errorMessage } from "../lib/errors";
 import type { CaseFile, PdfMetadata, ExtractedDocumentInfo } from "./types";
 
 export async function listFiles(caseId: string): Promise<CaseFile[]> {
   try {
     return await invoke<CaseFile[]>("list_files", { caseId });
   } catch (e) {
-    console.error("[API] Failed to list files:", e);
+    console.error("[API] Failed to list files:", errorMessage(e));
     return [];
   }
 }
@@ -35,7 +36,7 @@ export async function createFile(
     });
     return { file, error: null };
   } catch (e) {
-    const message = e instanceof Error ? e.message : String(e);
+    const message = errorMessage(e);
     console.error("[API] Failed to create file:", message);
     return { file: null, error: message };
   }
@@ -55,7 +56,7 @@ export async function updateFile(
       },
     });
   } catch (e) {
-    console.error("[API] Failed to update file:", e);
+    console.error("[API] Failed to update file:", errorMessage(e));
     return null;
   }
 }
@@ -65,7 +66,7 @@ export async function deleteFile(id: string): Promise<boolean> {
     await invoke("delete_file", { id });
     return true;
   } catch (e) {
-    console.error("[API] Failed to delete file:", e);
+    console.error("[API] Failed to delete file:", errorMessage(e));
     return false;
   }
 }
@@ -76,7 +77,7 @@ export async function extractPdfMetadata(
   try {
     return await invoke<PdfMetadata>("extract_pdf_metadata", { filePath });
   } catch (e) {
-    console.error("[API] Failed to extract PDF metadata:", e);
+    console.error("[API] Failed to extract PDF metadata:", errorMessage(e));
     return null;
   }
 }
@@ -89,7 +90,7 @@ export async function extractDocumentInfo(
       filePath,
     });
   } catch (e) {
-    console.error("[API] Failed to extract document info:", e);
+    console.error("[API] Failed to extract document info:", errorMessage(e));
     return null;
   }
 }
@@ -100,7 +101,7 @@ export async function generateAutoDescription(
   try {
     return await invoke<string>("generate_auto_description", { filePath });
   } catch (e) {
-    console.error("[API] Failed to generate description:", e);
+    console.error("[API] Failed to generate description:", errorMessage(e));
     return null;
   }
 }
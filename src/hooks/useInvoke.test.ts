@@ -1,7 +1,11 @@
 import { renderHook, waitFor, act } from "@testing-library/react";
 import { describe, it, expect, beforeEach, vi } from "vitest";
 import { useInvoke } from "./useInvoke";
-import { mockCase, mockDocument } from "@/test/mocks/tauri";
+import {
+  mockCase,
+  mockCommandError,
+  mockDocument,
+} from "@/test/mocks/tauri";
 import { invoke } from "@tauri-apps/api/core";
 
 // Get the mocked invoke
@@ -18,7 +22,7 @@ describe("useInvoke", () => {
         mockCase({ name: "Smith v Jones" }),
         mockCase({ name: "Acme Corp Merger" }),
       ];
-      mockInvoke.mockResolvedValueOnce(cases);
+      mockInvoke.mockResolvedValueOnce({ cases, total: cases.length });
 
       const { result } = renderHook(() => useInvoke());
 
@@ -33,7 +37,10 @@ describe("useInvoke", () => {
 
     it("sets loading state during fetch", async () => {
       mockInvoke.mockImplementation(
-        () => new Promise((resolve) => setTimeout(() => resolve([]), 100)),
+        () =>
+          new Promise((resolve) =>
+            setTimeout(() => resolve({ cases: [], total: 0 }), 100),
+          ),
       );
 
       const { result } = renderHook(() => useInvoke());
@@ -250,6 +257,56 @@ describe("useInvoke", () => {
       expect(success).toBe(false);
       expect(result.current.error).toBe("Delete failed");
     });
+
+    it("shows the message of a structured command error", async () => {
+      mockInvoke.mockRejectedValueOnce(
+        mockCommandError("not_found", "Case not found: case-123"),
+      );
+
+      const { result } = renderHook(() => useInvoke());
+
+      let success;
+      await act(async () => {
+        success = await result.current.deleteCase("case-123");
+      });
+
+      expect(success).toBe(false);
+      expect(result.current.error).toBe("Case not found: case-123");
+    });
+  });
+
+  describe("extractPdfMetadata", () => {
+    it("reports encrypted PDFs to the user", async () => {
+      mockInvoke.mockRejectedValueOnce(
+        mockCommandError("encrypted", "PDF is encrypted and needs a password"),
+      );
+
+      const { result } = renderHook(() => useInvoke());
+
+      let metadata;
+      await act(async () => {
+        metadata = await result.current.extractPdfMetadata("/docs/locked.pdf");
+      });
+
+      expect(metadata).toBeNull();
+      expect(result.current.error).toBe(
+        "PDF is encrypted and needs a password",
+      );
+    });
+
+    it("keeps other PDF errors out of the error state", async () => {
+      mockInvoke.mockRejectedValueOnce(
+        mockCommandError("invalid", "Failed to load PDF"),
+      );
+
+      const { result } = renderHook(() => useInvoke());
+
+      await act(async () => {
+        await result.current.extractPdfMetadata("/docs/broken.pdf");
+      });
+
+      expect(result.current.error).toBeNull();
+    });
   });
 
   describe("deleteDocument", () => {
@@ -284,7 +341,7 @@ describe("useInvoke", () => {
       expect(result.current.error).toBe("First error");
 
       // Second request succeeds
-      mockInvoke.mockResolvedValueOnce([]);
+      mockInvoke.mockResolvedValueOnce({ cases: [], total: 0 });
 
       await act(async () => {
         await result.current.listCases();
@@ -1,5 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import { useCallback, useState } from "react";
+import { errorKind, errorMessage } from "../lib/errors";
 
 export interface Case {
   id: string;
@@ -120,10 +121,12 @@ export function useInvoke() {
     setLoading(true);
     setError(null);
     try {
-      const cases = await invoke<Case[]>("list_cases");
-      return cases;
+      const page = await invoke<{ cases: Case[]; total: number }>(
+        "list_cases",
+      );
+      return page.cases;
     } catch (e) {
-      const message = e instanceof Error ? e.message : String(e);
+      const message = errorMessage(e);
       setError(message);
       return [];
     } finally {
@@ -145,7 +148,7 @@ export function useInvoke() {
         });
         return newCase;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         setError(message);
         return null;
       } finally {
@@ -165,7 +168,7 @@ export function useInvoke() {
         });
         return docs;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         setError(message);
         return [];
       } finally {
@@ -185,7 +188,7 @@ export function useInvoke() {
         });
         return doc;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         setError(message);
         return null;
       } finally {
@@ -203,7 +206,7 @@ export function useInvoke() {
         const doc = await invoke<Document>("load_document", { id });
         return doc;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         setError(message);
         return null;
       } finally {
@@ -223,7 +226,7 @@ export function useInvoke() {
         });
         return doc;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         setError(message);
         return null;
       } finally {
@@ -240,7 +243,7 @@ export function useInvoke() {
       await invoke("delete_case", { id });
       return true;
     } catch (e) {
-      const message = e instanceof Error ? e.message : String(e);
+      const message = errorMessage(e);
       setError(message);
       return false;
     } finally {
@@ -255,7 +258,7 @@ export function useInvoke() {
       await invoke("delete_document", { id });
       return true;
     } catch (e) {
-      const message = e instanceof Error ? e.message : String(e);
+      const message = errorMessage(e);
       setError(message);
       return false;
     } finally {
@@ -273,7 +276,13 @@ export function useInvoke() {
         console.log("[useInvoke] Metadata received from backend:", metadata);
         return metadata;
       } catch (e) {
-        console.error("[useInvoke] Failed to extract PDF metadata:", e);
+        const message = errorMessage(e);
+        console.error("[useInvoke] Failed to extract PDF metadata:", message);
+        // The user can fix these by supplying the right password
+        const kind = errorKind(e);
+        if (kind === "encrypted" || kind === "incorrect_password") {
+          setError(message);
+        }
         return null;
       }
     },
@@ -289,7 +298,7 @@ export function useInvoke() {
       const files = await invoke<CaseFile[]>("list_files", { caseId });
       return files;
     } catch (e) {
-      const message = e instanceof Error ? e.message : String(e);
+      const message = errorMessage(e);
       console.error("[useInvoke] Failed to list files:", message);
       setError(message);
       return [];
@@ -323,7 +332,7 @@ export function useInvoke() {
         console.log("[useInvoke] createFile success:", file);
         return { file, error: null };
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         console.error("[useInvoke] Failed to create file:", message, e);
         return { file: null, error: message };
       }
@@ -347,7 +356,7 @@ export function useInvoke() {
         });
         return file;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         console.error("[useInvoke] Failed to update file:", message);
         return null;
       }
@@ -360,7 +369,7 @@ export function useInvoke() {
       await invoke("delete_file", { id });
       return true;
     } catch (e) {
-      const message = e instanceof Error ? e.message : String(e);
+      const message = errorMessage(e);
       console.error("[useInvoke] Failed to delete file:", message);
       return false;
     }
@@ -378,7 +387,7 @@ export function useInvoke() {
         });
         return entries;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         console.error("[useInvoke] Failed to list entries:", message);
         return [];
       }
@@ -408,7 +417,7 @@ export function useInvoke() {
         });
         return entry;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         console.error("[useInvoke] Failed to create entry:", message);
         return null;
       }
@@ -434,7 +443,7 @@ export function useInvoke() {
         });
         return entry;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         console.error("[useInvoke] Failed to update entry:", message);
         return null;
       }
@@ -447,7 +456,7 @@ export function useInvoke() {
       await invoke("delete_entry", { id });
       return true;
     } catch (e) {
-      const message = e instanceof Error ? e.message : String(e);
+      const message = errorMessage(e);
       console.error("[useInvoke] Failed to delete entry:", message);
       return false;
     }
@@ -464,7 +473,7 @@ export function useInvoke() {
         });
         return entries;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         console.error("[useInvoke] Failed to reorder entries:", message);
         return [];
       }
@@ -484,7 +493,7 @@ export function useInvoke() {
         const exhibits = await invoke<Exhibit[]>("list_exhibits", { caseId });
         return exhibits;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         console.error("[useInvoke] Failed to list exhibits:", message);
         return [];
       }
@@ -498,7 +507,7 @@ export function useInvoke() {
         const files = await invoke<Exhibit[]>("list_staging_files", { caseId });
         return files;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         console.error("[useInvoke] Failed to list staging files:", message);
         return [];
       }
@@ -540,7 +549,7 @@ export function useInvoke() {
         console.log("[useInvoke] createExhibit success:", exhibit);
         return exhibit;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         console.error("[useInvoke] Failed to create exhibit:", message, e);
         return null;
       }
@@ -570,7 +579,7 @@ export function useInvoke() {
         });
         return exhibit;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         console.error("[useInvoke] Failed to update exhibit:", message);
         return null;
       }
@@ -590,7 +599,7 @@ export function useInvoke() {
         });
         return exhibit;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         console.error("[useInvoke] Failed to update exhibit status:", message);
         return null;
       }
@@ -614,7 +623,7 @@ export function useInvoke() {
         });
         return exhibit;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         console.error("[useInvoke] Failed to promote to bundled:", message);
         return null;
       }
@@ -627,7 +636,7 @@ export function useInvoke() {
       await invoke("delete_exhibit", { id });
       return true;
     } catch (e) {
-      const message = e instanceof Error ? e.message : String(e);
+      const message = errorMessage(e);
       console.error("[useInvoke] Failed to delete exhibit:", message);
       return false;
     }
@@ -644,7 +653,7 @@ export function useInvoke() {
         });
         return exhibits;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         console.error("[useInvoke] Failed to reorder exhibits:", message);
         return [];
       }
@@ -670,7 +679,7 @@ export function useInvoke() {
         });
         return result;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         console.error("[useInvoke] Failed to compile bundle:", message);
         return null;
       }
@@ -688,7 +697,7 @@ export function useInvoke() {
         });
         return entries;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         console.error("[useInvoke] Failed to preview TOC:", message);
         return [];
       }
@@ -706,7 +715,7 @@ export function useInvoke() {
         });
         return result;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         console.error("[useInvoke] Failed to validate bundle:", message);
         return null;
       }
@@ -725,7 +734,7 @@ export function useInvoke() {
         );
         return info;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         console.error("[useInvoke] Failed to extract document info:", message);
         return null;
       }
@@ -741,7 +750,7 @@ export function useInvoke() {
         });
         return description;
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         console.error("[useInvoke] Failed to generate description:", message);
         return null;
       }
@@ -29,7 +29,10 @@ import type {
 // ============================================================================
 
 const cases = {
-  list: (): Promise<Case[]> => invoke("list_cases"),
+  list: (): Promise<Case[]> =>
+    invoke<{ cases: Case[]; total: number }>("list_cases").then(
+      (page) => page.cases,
+    ),
 
   create: (request: CreateCaseRequest): Promise<Case> =>
     invoke("create_case", { request }),
@@ -0,0 +1,37 @@
+/**
+ * Command errors
+ *
+ * Database and PDF commands reject with `{ kind, message }` rather than a
+ * string. These helpers turn any rejection into something showable.
+ */
+
+export interface CommandError {
+  /** Stable identifier, e.g. "not_found", "invalid", "encrypted" */
+  kind: string;
+  message: string;
+}
+
+export function isCommandError(e: unknown): e is CommandError {
+  return (
+    typeof e === "object" &&
+    e !== null &&
+    typeof (e as CommandError).kind === "string" &&
+    typeof (e as CommandError).message === "string"
+  );
+}
+
+/** The error's `kind`, or undefined for plain errors and strings */
+export function errorKind(e: unknown): string | undefined {
+  return isCommandError(e) ? e.kind : undefined;
+}
+
+/** A message fit for the user from whatever a command rejected with */
+export function errorMessage(e: unknown): string {
+  if (isCommandError(e)) {
+    if (e.kind === "not_initialized") {
+      return "The database is still starting up. Try again in a moment.";
+    }
+    return e.message;
+  }
+  return e instanceof Error ? e.message : String(e);
+}
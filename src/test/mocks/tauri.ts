@@ -1,5 +1,6 @@
 import { vi } from "vitest";
 import type { Case, Document } from "@/hooks/useInvoke";
+import type { CommandError } from "@/lib/errors";
 
 // Get the mocked invoke function from the global mock
 export const getMockInvoke = () => {
@@ -26,6 +27,12 @@ export const mockDocument = (overrides?: Partial<Document>): Document => ({
   ...overrides,
 });
 
+// What database and PDF commands reject with
+export const mockCommandError = (
+  kind: string,
+  message: string,
+): CommandError => ({ kind, message });
+
 // Type for the mocked invoke function
 type InvokeArgs = {
   list_cases: void;
@@ -39,7 +46,7 @@ type InvokeArgs = {
 };
 
 type InvokeReturn = {
-  list_cases: Case[];
+  list_cases: { cases: Case[]; total: number };
   create_case: Case;
   list_documents: Document[];
   create_document: Document;
@@ -97,7 +104,7 @@ export function setupCrudMocks(initialCases: Case[] = []) {
 
   const mock = createMockInvoke();
 
-  mock.on("list_cases", () => cases);
+  mock.on("list_cases", () => ({ cases, total: cases.length }));
 
   mock.on("create_case", ({ request }) => {
     const newCase = mockCase({ name: request.name });
@@ -106,6 +113,9 @@ export function setupCrudMocks(initialCases: Case[] = []) {
   });
 
   mock.on("delete_case", ({ id }) => {
+    if (!cases.some((c) => c.id === id)) {
+      throw mockCommandError("not_found", `Case not found: ${id}`);
+    }
     cases = cases.filter((c) => c.id !== id);
     documents = documents.filter((d) => d.case_id !== id);
   });